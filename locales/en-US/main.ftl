@@ -0,0 +1,301 @@
+## Messages
+
+region-issues-title = The region list has issues
+region-issues-more = ... run the `validate` command for the full list
+region-db-updated-title = Region list updated
+restart-for-regions = Restart the application to use the new region list.
+pings-unavailable-title = Pings are unavailable
+pings-unavailable-content = Your system may not support this feature
+file-selection-failed-title = Unable to read the file selection
+record-failed-title = Cannot record pings
+record-unavailable = Pings are unavailable on this system
+record-open-failed =
+    Failed to open the recording file:
+
+    { $error }
+record-stopped-title = Ping recording stopped
+record-write-failed =
+    Failed to write the recording due to an error:
+
+    { $error }
+no-selection-title = No regions selected
+no-selection-content = Please select at least one region
+enable-failed-title = Cannot enable blocking
+enable-failed-content =
+    Failed to activate blocking due to an error:
+
+    { $error }
+enabled-title = Server list updated
+enabled-content = Restart Overwatch to avoid connection issues.
+disable-failed-title = Cannot disable blocking
+disable-failed-content =
+    Failed to deactivate blocking due to an error:
+    { $error }
+disabled-title = Server blocking disabled
+disabled-content = Restart Overwatch for the changes to apply.
+daemon-failed-content =
+    The blocking process stopped before blocking anything ({ $status }).
+daemon-exited-title = Server blocking stopped
+daemon-exited-content =
+    The blocking process exited unexpectedly ({ $status }) and removed its rules, all servers are reachable again.
+daemon-crashed-content =
+    The blocking process exited unexpectedly ({ $status }), its rules may still block the running game until blocking is enabled again or the game restarts.
+game-moved-title = Overwatch detected
+game-moved-content =
+    { $count ->
+        [one] Game process { $pids } is now subject to the blocking.
+       *[other] Game processes { $pids } are now subject to the blocking.
+    }
+config-load-failed =
+    Failed to load the configuration:
+
+    { $error }
+config-save-failed =
+    Failed to save the configuration:
+
+    { $error }
+settings-save-failed-title = Cannot save the settings
+modal-close = close
+modal-next =
+    { $pending ->
+        [one] next (1 more)
+       *[other] next ({ $pending } more)
+    }
+toast-dismiss = Click to dismiss
+log-title = Messages
+log-empty = No message yet.
+log-clear = clear
+
+## File dialogs
+
+game-dialog-title = Find your Overwatch installation
+record-dialog-title = Record ping measurements
+
+## Bottom bar
+
+record = record
+record-stop = stop recording
+record-to = Recording to { $path }
+record-hover = Record ping measurements to a CSV or JSON lines file
+customize = customize
+customize-hover = Add custom regions and change the prefixes of existing ones
+log = messages
+log-hover = Show the messages received since the application started
+activity = activity
+activity-hover = Show the dropped traffic and the servers the game talks to
+enable = enable
+disable = disable
+select-game-path = select game path
+
+## Tray icon
+
+app-title = ow2 server picker
+tray-enabled = Server blocking enabled
+tray-enabled-profile = Server blocking enabled with the { $profile } profile
+tray-disabled = Server blocking disabled
+tray-show = Show window
+tray-hide = Hide window
+tray-quit = Quit
+
+## Region list
+
+regions-heading = Select desired matchmaking regions:
+auto = auto
+auto-hover = Select regions based on their ping
+block-mode-select = block: { $mode }
+group-select = group: { $grouping }
+group-hover = Group regions by continent or provider
+no-region-matches = No region matches the filter.
+region-pending-hover = Changed since blocking was enabled, apply the changes to use it
+region-not-applied = not applied
+region-description = { $name } ({ $code }), ping { $ping }
+region-description-v6 = , IPv6 ping { $ping }
+region-description-pending = , change not applied
+ping-unreachable = Unreachable
+traceroute = traceroute
+traceroute-v6 = traceroute (IPv6)
+group-select-hover = Select or unselect every region of the group
+group-show-only = show only this group
+group-show-all = show all groups
+
+## Profiles
+
+profile-select = profile: { $name }
+profile-none = none
+profile-select-hover = Select the regions of a saved profile and enable blocking
+profiles = profiles
+profiles-hover = Create, rename and delete profiles
+profiles-title = Profiles
+profiles-empty = No profile yet, select regions and create one below.
+profile-summary =
+    { $regions ->
+        [one] 1 region
+       *[other] { $regions } regions
+    }, block: { $mode }
+profile-save-hover = Replace with the current selection and block mode
+profile-new-hint = new profile name
+profile-create-hover = Save the current selection and block mode
+profile-name-missing = the profile needs a name
+profile-name-taken = a profile named "{ $name }" already exists
+
+## Pending changes
+
+pending-mode = Block mode changed since blocking was enabled
+pending-regions =
+    { $count ->
+        [one] 1 region changed since blocking was enabled
+       *[other] { $count } regions changed since blocking was enabled
+    }
+apply-changes = apply changes
+apply-changes-hover = Enable blocking with the current selection
+revert = revert
+revert-hover = Select the regions blocking was enabled with
+
+## Filters
+
+filter-hint = filter by name, code or key
+filter-hover = Ctrl+F to focus, Escape to clear
+filter-clear-hover = Clear the filters
+filter-selected = selected
+filter-selected-hover = Only show the selected regions
+filter-reachable = reachable
+filter-reachable-hover = Only show the regions answering pings
+filter-below = below
+filter-below-hover = Only show the regions faster than this latency
+
+## Selection and sorting
+
+select = select
+select-hover = Change the selection of every shown region
+select-all = all
+select-none = none
+select-invert = invert
+select-best = best
+select-best-hover = Select the regions with the lowest ping
+sort-by = sort by
+sort-then-by = then by
+sort-nothing = nothing
+sort-ascending = ascending
+sort-descending = descending
+sort-hover =
+    Sorted by { $by } ({ $ordering }).
+    • Click to change the sorting.
+    • Right Click to change ordering.
+
+## Activity
+
+activity-title = Blocking activity
+activity-dropped = Dropped this session
+activity-unlisted = unlisted addresses
+activity-nothing-blocked = Nothing is blocked.
+activity-packets =
+    { $packets ->
+        [one] 1 packet
+       *[other] { $packets } packets
+    }
+activity-connections = Game connections
+activity-no-connection = No connection seen yet.
+activity-copy-unknown =
+    { $count ->
+        [one] copy 1 unknown address
+       *[other] copy { $count } unknown addresses
+    }
+activity-copy-unknown-hover = Copy them as prefixes to add them to a region
+activity-unknown = unknown
+activity-ago = { $duration } ago
+activity-disabled = Blocking is not enabled.
+
+## Custom regions
+
+custom-title = Custom regions
+custom-load-failed-title = Cannot edit custom regions
+custom-saved-title = Custom regions saved
+custom-save-failed-title = Cannot save custom regions
+custom-name = name
+custom-code = code
+custom-ping = ping
+custom-ping6 = ping (IPv6)
+custom-optional = optional
+custom-continent = continent
+custom-prefixes = prefixes
+custom-cidr-hint = one CIDR per line
+custom-remove = remove
+custom-add-region = add region
+custom-overrides = Prefix overrides
+custom-extra = extra
+custom-excluded = excluded
+custom-override-region = override region...
+custom-no-name = a custom region has no name
+custom-no-code = custom region { $name } has no code
+custom-invalid-ping = invalid ping target of { $name }
+custom-invalid-ping6 = invalid IPv6 ping target of { $name }
+custom-invalid-prefixes = in { $name }
+custom-no-prefixes = custom region { $name } has no prefixes
+custom-duplicate-key = several custom regions use the key { $key }
+custom-invalid-override = in the overrides of { $key }
+save = save
+ok = ok
+cancel = cancel
+rename = rename
+delete = delete
+create = create
+
+## Automatic selection
+
+auto-title = Automatic selection
+auto-below = below
+auto-best = best
+auto-regions = regions
+auto-periodic = re-evaluate every
+auto-preview = preview
+auto-no-match = No region matches the criteria.
+auto-apply = apply
+auto-apply-hover = Select these regions and enable blocking
+auto-discard = discard
+
+## Traceroute
+
+traceroute-title = Traceroute to { $name }
+traceroute-reached = reached in { $hops } hops
+traceroute-not-reached = not reached
+traceroute-running = tracing...
+
+## Options
+
+block-unselected = unselected
+block-unselected-description = Block the servers of the unselected regions
+block-allowlist = allowlist
+block-allowlist-description = Block every server except the selected regions and Battle.net services, servers missing from the region list are blocked too
+grouping-none = none
+grouping-continent = continent
+grouping-provider = provider
+sort-name = name
+sort-code = code
+sort-provider = provider
+sort-ping = ping
+sort-loss = loss
+sort-selection = selection
+continent-north-america = North America
+continent-south-america = South America
+continent-europe = Europe
+continent-middle-east = Middle East
+continent-asia = Asia
+continent-oceania = Oceania
+continent-unknown = Unknown
+provider-blizzard = Blizzard
+provider-google = Google Cloud
+provider-custom = Custom
+provider-other = Other
+
+## Region list issues
+
+issue-invalid = { $key }: invalid prefix "{ $value }" ({ $error })
+issue-host-bits = { $key }: { $prefix } has host bits set, it covers { $network }
+issue-duplicate = { $key }: { $prefix } is listed twice
+issue-subsumed = { $key }: { $prefix } is already covered by { $by }
+issue-overlap = { $key }: { $prefix } overlaps { $other_prefix } of { $other_key }
+issue-cache-ignored = ignoring the cached region database: { $error }
+issue-custom-ignored = ignoring custom regions: { $error }
+issue-override-unknown = overrides of unknown region { $key } are ignored
+issue-excluded-unused = excluded prefix { $prefix } of { $key } does not overlap any of its prefixes
+issue-custom-exists = custom region { $key } already exists, it is ignored
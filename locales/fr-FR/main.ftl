@@ -0,0 +1,315 @@
+## Messages
+
+region-issues-title = La liste des régions comporte des problèmes
+region-issues-more = ... lancez la commande `validate` pour la liste complète
+region-db-updated-title = Liste des régions mise à jour
+restart-for-regions = Redémarrez l'application pour utiliser la nouvelle liste des régions.
+pings-unavailable-title = Les pings sont indisponibles
+pings-unavailable-content = Votre système ne prend peut-être pas en charge cette fonctionnalité
+file-selection-failed-title = Impossible de lire le fichier sélectionné
+record-failed-title = Impossible d'enregistrer les pings
+record-unavailable = Les pings sont indisponibles sur ce système
+record-open-failed =
+    Impossible d'ouvrir le fichier d'enregistrement :
+
+    { $error }
+record-stopped-title = Enregistrement des pings arrêté
+record-write-failed =
+    Impossible d'écrire l'enregistrement à cause d'une erreur :
+
+    { $error }
+no-selection-title = Aucune région sélectionnée
+no-selection-content = Veuillez sélectionner au moins une région
+enable-failed-title = Impossible d'activer le blocage
+enable-failed-content =
+    Le blocage n'a pas pu être activé à cause d'une erreur :
+
+    { $error }
+enabled-title = Liste des serveurs mise à jour
+enabled-content = Redémarrez Overwatch pour éviter les problèmes de connexion.
+disable-failed-title = Impossible de désactiver le blocage
+disable-failed-content =
+    Le blocage n'a pas pu être désactivé à cause d'une erreur :
+    { $error }
+disabled-title = Blocage des serveurs désactivé
+disabled-content = Redémarrez Overwatch pour appliquer les changements.
+daemon-failed-content =
+    Le processus de blocage s'est arrêté avant de bloquer quoi que ce soit ({ $status }).
+daemon-exited-title = Blocage des serveurs arrêté
+daemon-exited-content =
+    Le processus de blocage s'est arrêté de manière inattendue ({ $status }) et a retiré ses règles, tous les serveurs sont de nouveau accessibles.
+daemon-crashed-content =
+    Le processus de blocage s'est arrêté de manière inattendue ({ $status }), ses règles peuvent encore bloquer le jeu en cours jusqu'à ce que le blocage soit réactivé ou que le jeu redémarre.
+game-moved-title = Overwatch détecté
+game-moved-content =
+    { $count ->
+        [one] Le processus du jeu { $pids } est maintenant soumis au blocage.
+       *[other] Les processus du jeu { $pids } sont maintenant soumis au blocage.
+    }
+config-load-failed =
+    Impossible de charger la configuration :
+
+    { $error }
+config-save-failed =
+    Impossible d'enregistrer la configuration :
+
+    { $error }
+settings-save-failed-title = Impossible d'enregistrer les réglages
+modal-close = fermer
+modal-next =
+    { $pending ->
+        [one] suivant (1 de plus)
+       *[other] suivant ({ $pending } de plus)
+    }
+toast-dismiss = Cliquer pour masquer
+log-title = Messages
+log-empty = Aucun message pour l'instant.
+log-clear = effacer
+
+## File dialogs
+
+game-dialog-title = Trouvez votre installation d'Overwatch
+record-dialog-title = Enregistrer les mesures de ping
+
+## Bottom bar
+
+record = enregistrer
+record-stop = arrêter l'enregistrement
+record-to = Enregistrement dans { $path }
+record-hover = Enregistrer les mesures de ping dans un fichier CSV ou JSON lines
+customize = personnaliser
+customize-hover = Ajouter des régions personnalisées et modifier les préfixes des régions existantes
+log = messages
+log-hover = Afficher les messages reçus depuis le lancement de l'application
+activity = activité
+activity-hover = Afficher le trafic bloqué et les serveurs contactés par le jeu
+enable = activer
+disable = désactiver
+select-game-path = choisir le jeu
+
+## Tray icon
+
+app-title = ow2 server picker
+tray-enabled = Blocage des serveurs activé
+tray-enabled-profile = Blocage des serveurs activé avec le profil { $profile }
+tray-disabled = Blocage des serveurs désactivé
+tray-show = Afficher la fenêtre
+tray-hide = Masquer la fenêtre
+tray-quit = Quitter
+
+## Region list
+
+regions-heading = Choisissez les régions de matchmaking :
+auto = auto
+auto-hover = Sélectionner les régions selon leur ping
+block-mode-select = blocage : { $mode }
+group-select = grouper : { $grouping }
+group-hover = Grouper les régions par continent ou par hébergeur
+no-region-matches = Aucune région ne correspond au filtre.
+region-pending-hover = Modifiée depuis l'activation du blocage, appliquez les changements pour l'utiliser
+region-not-applied = non appliqué
+region-description = { $name } ({ $code }), ping { $ping }
+region-description-v6 = , ping IPv6 { $ping }
+region-description-pending = , changement non appliqué
+ping-unreachable = Injoignable
+traceroute = traceroute
+traceroute-v6 = traceroute (IPv6)
+group-select-hover = Sélectionner ou désélectionner toutes les régions du groupe
+group-show-only = afficher uniquement ce groupe
+group-show-all = afficher tous les groupes
+
+## Profiles
+
+profile-select = profil : { $name }
+profile-none = aucun
+profile-select-hover = Sélectionner les régions d'un profil enregistré et activer le blocage
+profiles = profils
+profiles-hover = Créer, renommer et supprimer des profils
+profiles-title = Profils
+profiles-empty = Aucun profil pour l'instant, sélectionnez des régions et créez-en un ci-dessous.
+profile-summary =
+    { $regions ->
+        [one] 1 région
+       *[other] { $regions } régions
+    }, blocage : { $mode }
+profile-save-hover = Remplacer par la sélection et le mode de blocage actuels
+profile-new-hint = nom du nouveau profil
+profile-create-hover = Enregistrer la sélection et le mode de blocage actuels
+profile-name-missing = le profil doit avoir un nom
+profile-name-taken = un profil nommé « { $name } » existe déjà
+
+## Pending changes
+
+pending-mode = Le mode de blocage a changé depuis l'activation du blocage
+pending-regions =
+    { $count ->
+        [one] 1 région modifiée depuis l'activation du blocage
+       *[other] { $count } régions modifiées depuis l'activation du blocage
+    }
+apply-changes = appliquer
+apply-changes-hover = Activer le blocage avec la sélection actuelle
+revert = annuler
+revert-hover = Sélectionner les régions avec lesquelles le blocage a été activé
+
+## Filters
+
+filter-hint = filtrer par nom, code ou clé
+filter-hover = Ctrl+F pour saisir, Échap pour effacer
+filter-clear-hover = Effacer les filtres
+filter-selected = sélectionnées
+filter-selected-hover = Afficher uniquement les régions sélectionnées
+filter-reachable = joignables
+filter-reachable-hover = Afficher uniquement les régions qui répondent aux pings
+filter-below = sous
+filter-below-hover = Afficher uniquement les régions plus rapides que cette latence
+
+## Selection and sorting
+
+select = sélection
+select-hover = Modifier la sélection de toutes les régions affichées
+select-all = tout
+select-none = rien
+select-invert = inverser
+select-best = meilleures
+select-best-hover = Sélectionner les régions avec le ping le plus bas
+sort-by = trier par
+sort-then-by = puis par
+sort-nothing = rien
+sort-ascending = croissant
+sort-descending = décroissant
+sort-hover =
+    Trié par { $by } ({ $ordering }).
+    • Cliquez pour changer le tri.
+    • Clic droit pour inverser l'ordre.
+
+## Activity
+
+activity-title = Activité du blocage
+activity-dropped = Bloqué pendant cette session
+activity-unlisted = adresses non répertoriées
+activity-nothing-blocked = Rien n'est bloqué.
+activity-packets =
+    { $packets ->
+        [one] 1 paquet
+       *[other] { $packets } paquets
+    }
+activity-connections = Connexions du jeu
+activity-no-connection = Aucune connexion observée pour l'instant.
+activity-copy-unknown =
+    { $count ->
+        [one] copier 1 adresse inconnue
+       *[other] copier { $count } adresses inconnues
+    }
+activity-copy-unknown-hover = Les copier sous forme de préfixes pour les ajouter à une région
+activity-unknown = inconnue
+activity-ago = il y a { $duration }
+activity-disabled = Le blocage n'est pas activé.
+
+## Custom regions
+
+custom-title = Régions personnalisées
+custom-load-failed-title = Impossible de modifier les régions personnalisées
+custom-saved-title = Régions personnalisées enregistrées
+custom-save-failed-title = Impossible d'enregistrer les régions personnalisées
+custom-name = nom
+custom-code = code
+custom-ping = ping
+custom-ping6 = ping (IPv6)
+custom-optional = facultatif
+custom-continent = continent
+custom-prefixes = préfixes
+custom-cidr-hint = un CIDR par ligne
+custom-remove = retirer
+custom-add-region = ajouter une région
+custom-overrides = Modifications de préfixes
+custom-extra = ajoutés
+custom-excluded = exclus
+custom-override-region = modifier une région...
+custom-no-name = une région personnalisée n'a pas de nom
+custom-no-code = la région personnalisée { $name } n'a pas de code
+custom-invalid-ping = cible de ping invalide pour { $name }
+custom-invalid-ping6 = cible de ping IPv6 invalide pour { $name }
+custom-invalid-prefixes = dans { $name }
+custom-no-prefixes = la région personnalisée { $name } n'a pas de préfixe
+custom-duplicate-key = plusieurs régions personnalisées utilisent la clé { $key }
+custom-invalid-override = dans les modifications de { $key }
+save = enregistrer
+ok = ok
+cancel = annuler
+rename = renommer
+delete = supprimer
+create = créer
+
+## Automatic selection
+
+auto-title = Sélection automatique
+auto-below = sous
+auto-best = les
+auto-regions = meilleures régions
+auto-periodic = réévaluer toutes les
+auto-preview = aperçu
+auto-no-match = Aucune région ne correspond aux critères.
+auto-apply = appliquer
+auto-apply-hover = Sélectionner ces régions et activer le blocage
+auto-discard = ignorer
+
+## Traceroute
+
+traceroute-title = Traceroute vers { $name }
+traceroute-reached = atteint en { $hops } sauts
+traceroute-not-reached = non atteint
+traceroute-running = en cours...
+
+## Options
+
+block-unselected = non sélectionnées
+block-unselected-description = Bloquer les serveurs des régions non sélectionnées
+block-allowlist = liste blanche
+block-allowlist-description = Bloquer tous les serveurs sauf ceux des régions sélectionnées et de Battle.net, les serveurs absents de la liste des régions sont aussi bloqués
+grouping-none = aucun
+grouping-continent = continent
+grouping-provider = hébergeur
+sort-name = nom
+sort-code = code
+sort-provider = hébergeur
+sort-ping = ping
+sort-loss = pertes
+sort-selection = sélection
+continent-north-america = Amérique du Nord
+continent-south-america = Amérique du Sud
+continent-europe = Europe
+continent-middle-east = Moyen-Orient
+continent-asia = Asie
+continent-oceania = Océanie
+continent-unknown = Inconnu
+provider-blizzard = Blizzard
+provider-google = Google Cloud
+provider-custom = Personnalisé
+provider-other = Autre
+
+## Region list issues
+
+issue-invalid = { $key } : préfixe invalide "{ $value }" ({ $error })
+issue-host-bits = { $key } : { $prefix } a des bits d'hôte, il couvre { $network }
+issue-duplicate = { $key } : { $prefix } est listé deux fois
+issue-subsumed = { $key } : { $prefix } est déjà couvert par { $by }
+issue-overlap = { $key } : { $prefix } chevauche { $other_prefix } de { $other_key }
+issue-cache-ignored = base de régions en cache ignorée : { $error }
+issue-custom-ignored = régions personnalisées ignorées : { $error }
+issue-override-unknown = les modifications de la région inconnue { $key } sont ignorées
+issue-excluded-unused = le préfixe exclu { $prefix } de { $key } ne chevauche aucun de ses préfixes
+issue-custom-exists = la région personnalisée { $key } existe déjà, elle est ignorée
+
+## Region names
+
+regionname-blizzard-ord1 = États-Unis - Centre
+regionname-blizzard-las1 = États-Unis - Ouest
+regionname-google-europe-north1 = Finlande
+regionname-google-asia-southeast1 = Singapour
+regionname-google-southamerica-east1 = Brésil
+regionname-google-asia-northeast1 = Tokyo
+regionname-google-me-central2 = Arabie saoudite
+regionname-blizzard-icn1 = Corée du Sud
+regionname-blizzard-syd2 = Australie
+regionname-blizzard-tpe1 = Taïwan
+regionname-blizzard-ams1 = Pays-Bas
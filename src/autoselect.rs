@@ -0,0 +1,118 @@
+use std::collections::BTreeSet;
+use std::time::{Duration, Instant};
+
+use indexmap::IndexMap;
+use iter_tools::Itertools;
+
+use crate::regions::RegionEntry;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum AutoSelectMode {
+    /// Select every region with an average ping below the threshold.
+    Threshold,
+    /// Select the regions with the lowest average ping.
+    Best,
+}
+
+pub struct AutoSelect {
+    pub mode: AutoSelectMode,
+
+    /// Highest average ping of a selected region, in milliseconds.
+    pub threshold_ms: u32,
+
+    /// Amount of regions selected in [`AutoSelectMode::Best`].
+    pub best_n: usize,
+
+    /// Whether to re-evaluate the selection periodically.
+    pub periodic: bool,
+
+    /// Delay between periodic evaluations, in minutes.
+    pub period_mins: u32,
+
+    /// Whether the settings window is shown.
+    pub open: bool,
+
+    /// Selection waiting for the user's confirmation.
+    pub proposal: Option<BTreeSet<String>>,
+
+    last_evaluation: Option<Instant>,
+}
+
+impl AutoSelect {
+    /// Computes the keys of the regions that would be selected.
+    pub fn evaluate(&self, regions: &IndexMap<String, RegionEntry>) -> BTreeSet<String> {
+        let reachable = regions
+            .iter()
+            .filter_map(|(key, entry)| Some((key, entry.stats.avg()?)));
+
+        match self.mode {
+            AutoSelectMode::Threshold => reachable
+                .filter(|(_, avg)| avg.as_millis() < u128::from(self.threshold_ms))
+                .map(|(key, _)| key.clone())
+                .collect(),
+            AutoSelectMode::Best => reachable
+                .sorted_by_key(|(_, avg)| *avg)
+                .take(self.best_n)
+                .map(|(key, _)| key.clone())
+                .collect(),
+        }
+    }
+
+    /// Evaluates the selection and stores it as a proposal.
+    pub fn propose(&mut self, regions: &IndexMap<String, RegionEntry>) {
+        self.proposal = Some(self.evaluate(regions));
+        self.last_evaluation = Some(Instant::now());
+    }
+
+    /// Time left until the next periodic evaluation, if one is scheduled.
+    pub fn next_evaluation(&self) -> Option<Duration> {
+        if !self.periodic || self.proposal.is_some() {
+            return None;
+        }
+
+        let period = Duration::from_secs(u64::from(self.period_mins) * 60);
+        Some(
+            self.last_evaluation
+                .map_or(Duration::ZERO, |last| period.saturating_sub(last.elapsed())),
+        )
+    }
+
+    /// Runs a periodic evaluation when it is due.
+    ///
+    /// A proposal is only kept when it differs from the current selection.
+    pub fn tick(&mut self, regions: &IndexMap<String, RegionEntry>) {
+        if self.next_evaluation() != Some(Duration::ZERO) {
+            return;
+        }
+
+        self.propose(regions);
+
+        let current = regions
+            .iter()
+            .filter(|(_, entry)| entry.selected)
+            .map(|(key, _)| key)
+            .collect::<BTreeSet<_>>();
+        let proposal = self.proposal.as_ref().unwrap();
+
+        if proposal.is_empty() || proposal.iter().collect::<BTreeSet<_>>() == current {
+            self.proposal = None;
+        } else {
+            self.open = true;
+        }
+    }
+}
+
+impl Default for AutoSelect {
+    fn default() -> Self {
+        Self {
+            mode: AutoSelectMode::Threshold,
+            threshold_ms: 80,
+            best_n: 3,
+            periodic: false,
+            period_mins: 10,
+            open: false,
+            proposal: None,
+            last_evaluation: None,
+        }
+    }
+}
@@ -0,0 +1,240 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use anyhow::Context;
+use ipnetwork::IpNetwork;
+use serde::{Deserialize, Serialize};
+
+use crate::cidr;
+use crate::i18n::tr;
+use crate::prefixes::Region;
+
+/// Always reachable in [`BlockMode::Allowlist`].
+///
+/// Battle.net services (login, chat, matchmaking) are announced by Blizzard's AS57976, it
+/// also announces some game servers, those of unselected regions are still blocked.
+const ALWAYS_ALLOWED: &[&str] = &[
+    // AS57976
+    "5.42.160.0/19",
+    "24.105.0.0/18",
+    "37.244.0.0/18",
+    "137.221.64.0/18",
+    "185.60.112.0/22",
+    // local networks
+    "10.0.0.0/8",
+    "127.0.0.0/8",
+    "169.254.0.0/16",
+    "172.16.0.0/12",
+    "192.168.0.0/16",
+    "::1/128",
+    "fc00::/7",
+    "fe80::/10",
+];
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BlockMode {
+    /// Block the prefixes of the unselected regions.
+    #[default]
+    // configurations saved with the dropped strict mode fall back to this one
+    #[serde(alias = "strict", alias = "keepselected")]
+    Unselected,
+    /// Block everything except the selected regions and Battle.net services.
+    Allowlist,
+}
+
+impl BlockMode {
+    pub const ALL: [BlockMode; 2] = [BlockMode::Unselected, BlockMode::Allowlist];
+
+    pub fn description(&self) -> String {
+        match self {
+            BlockMode::Unselected => tr!("block-unselected-description"),
+            BlockMode::Allowlist => tr!("block-allowlist-description"),
+        }
+    }
+}
+
+impl Display for BlockMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            BlockMode::Unselected => tr!("block-unselected"),
+            BlockMode::Allowlist => tr!("block-allowlist"),
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// A prefix to block, along with the region it is blocked for.
+///
+/// Written as `region=prefix` on the daemon's command line, or as the bare prefix.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BlockRule {
+    /// Key of the region, `None` for addresses of no region.
+    pub region: Option<String>,
+    pub prefix: IpNetwork,
+}
+
+impl Display for BlockRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.region {
+            Some(region) => write!(f, "{region}={}", self.prefix),
+            None => write!(f, "{}", self.prefix),
+        }
+    }
+}
+
+impl FromStr for BlockRule {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (region, prefix) = match s.rsplit_once('=') {
+            Some((region, prefix)) => (Some(region.to_string()), prefix),
+            None => (None, s),
+        };
+
+        Ok(Self {
+            region,
+            prefix: prefix
+                .parse()
+                .with_context(|| format!("{prefix} is not a valid CIDR"))?,
+        })
+    }
+}
+
+/// Computes the prefixes to block for the game, aggregated per region.
+///
+/// An address is only blocked once, for the first region listing it. `allowlist` extends the
+/// addresses that are always reachable in [`BlockMode::Allowlist`].
+pub fn blocked_prefixes<'a>(
+    mode: BlockMode,
+    regions: impl IntoIterator<Item = (&'a Region, bool)>,
+    allowlist: &[IpNetwork],
+) -> Vec<BlockRule> {
+    let mut selected = Vec::new();
+    let mut unselected = Vec::new();
+    for (region, is_selected) in regions {
+        if is_selected {
+            selected.extend(region.prefixes.iter().copied());
+        } else {
+            unselected.push(region);
+        }
+    }
+
+    let mut blocked = Vec::new();
+    let mut covered = Vec::new();
+    let mut block = |region: Option<&String>, prefixes: Vec<IpNetwork>| {
+        let prefixes = cidr::subtract(prefixes, covered.iter().copied());
+        covered = cidr::aggregate(covered.drain(..).chain(prefixes.iter().copied()));
+        blocked.extend(prefixes.into_iter().map(|prefix| BlockRule {
+            region: region.cloned(),
+            prefix,
+        }));
+    };
+
+    for region in unselected {
+        let prefixes = match mode {
+            BlockMode::Unselected => region.prefixes.clone(),
+            BlockMode::Allowlist => {
+                cidr::subtract(region.prefixes.iter().copied(), selected.iter().copied())
+            }
+        };
+        block(Some(&region.key), prefixes);
+    }
+
+    if mode == BlockMode::Allowlist {
+        let allowed = ALWAYS_ALLOWED
+            .iter()
+            .map(|prefix| prefix.parse().expect("always allowed prefixes are valid"))
+            .chain(allowlist.iter().copied())
+            .chain(selected.iter().copied());
+
+        block(None, cidr::complement(allowed));
+    }
+
+    blocked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn region(key: &str, prefixes: &[&str]) -> Region {
+        Region {
+            key: key.into(),
+            name: key.into(),
+            code: key.into(),
+            ping: "192.0.2.1".parse().unwrap(),
+            ping6: None,
+            continent: Default::default(),
+            prefixes: prefixes.iter().map(|p| p.parse().unwrap()).collect(),
+        }
+    }
+
+    fn rules(rules: &[BlockRule]) -> Vec<String> {
+        rules.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn rules_keep_their_region() {
+        let a = region("a", &["10.0.0.0/24", "10.0.1.0/24"]);
+        let b = region("b", &["10.0.2.0/24", "10.0.0.0/24"]);
+        let c = region("c", &["10.1.0.0/16"]);
+
+        let blocked = blocked_prefixes(
+            BlockMode::Unselected,
+            [(&a, false), (&b, false), (&c, true)],
+            &[],
+        );
+
+        // adjacent prefixes of different regions stay apart, shared ones count for the first
+        assert_eq!(rules(&blocked), ["a=10.0.0.0/23", "b=10.0.2.0/24"]);
+    }
+
+    #[test]
+    fn always_allowed_parses() {
+        for prefix in ALWAYS_ALLOWED {
+            assert!(cidr::parse(prefix).is_ok(), "{prefix}");
+        }
+    }
+
+    #[test]
+    fn allowlist_blocks_more() {
+        let a = region("a", &["10.0.0.0/24"]);
+        let b = region("b", &["10.0.1.0/24"]);
+        let c = region("c", &["10.0.2.0/24"]);
+        let regions = [(&a, false), (&b, true), (&c, false)];
+
+        let prefixes = |mode| {
+            blocked_prefixes(mode, regions, &[])
+                .into_iter()
+                .map(|rule| rule.prefix)
+                .collect::<Vec<_>>()
+        };
+        let unselected = prefixes(BlockMode::Unselected);
+        let allowlist = prefixes(BlockMode::Allowlist);
+
+        assert!(cidr::subtract(unselected, allowlist.iter().copied()).is_empty());
+        // addresses missing from the region list are blocked too
+        let unlisted = "198.51.100.0/24".parse().unwrap();
+        assert!(
+            allowlist
+                .iter()
+                .any(|prefix| cidr::contains(prefix, &unlisted))
+        );
+        // but not the selected region
+        let selected = "10.0.1.0/24".parse().unwrap();
+        assert!(
+            !allowlist
+                .iter()
+                .any(|prefix| cidr::overlaps(prefix, &selected))
+        );
+    }
+
+    #[test]
+    fn rule_round_trip() {
+        for value in ["blizzard/ord1=10.0.0.0/24", "2001:db8::/32"] {
+            assert_eq!(value.parse::<BlockRule>().unwrap().to_string(), value);
+        }
+        assert!("a=10.0.0.0/33".parse::<BlockRule>().is_err());
+    }
+}
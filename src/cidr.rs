@@ -0,0 +1,251 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use anyhow::{Context, Result, ensure};
+use ipnetwork::IpNetwork;
+use iter_tools::Itertools;
+
+/// Whether `inner` lies entirely within `outer`.
+pub fn contains(outer: &IpNetwork, inner: &IpNetwork) -> bool {
+    outer.prefix() <= inner.prefix() && outer.contains(inner.network())
+}
+
+/// Whether the two networks share any address.
+pub fn overlaps(a: &IpNetwork, b: &IpNetwork) -> bool {
+    contains(a, b) || contains(b, a)
+}
+
+/// Parses a CIDR, rejecting the ones with host bits set (ex: `10.0.0.1/8`).
+pub fn parse(value: &str) -> Result<IpNetwork> {
+    let network: IpNetwork = value
+        .parse()
+        .with_context(|| format!("{value} is not a valid CIDR"))?;
+    ensure!(
+        network.ip() == network.network(),
+        "{value} has host bits set, did you mean {}/{}?",
+        network.network(),
+        network.prefix()
+    );
+
+    Ok(network)
+}
+
+/// Parses a list of CIDRs separated by commas or whitespace.
+pub fn parse_list(value: &str) -> Result<Vec<IpNetwork>> {
+    value
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|cidr| !cidr.is_empty())
+        .map(parse)
+        .collect()
+}
+
+/// Merges contained and adjacent networks into the smallest equivalent set, per family.
+///
+/// The result is sorted, IPv4 networks first.
+pub fn aggregate(networks: impl IntoIterator<Item = IpNetwork>) -> Vec<IpNetwork> {
+    let [v4, v6] = split(networks);
+
+    join(aggregate_family(v4, 32), aggregate_family(v6, 128))
+}
+
+/// Addresses of `networks` that are not part of `removed`, aggregated.
+pub fn subtract(
+    networks: impl IntoIterator<Item = IpNetwork>,
+    removed: impl IntoIterator<Item = IpNetwork>,
+) -> Vec<IpNetwork> {
+    let [v4, v6] = split(networks);
+    let [removed_v4, removed_v6] = split(removed);
+
+    join(
+        subtract_family(v4, &removed_v4, 32),
+        subtract_family(v6, &removed_v6, 128),
+    )
+}
+
+/// Every address of both families that is not part of `networks`, aggregated.
+pub fn complement(networks: impl IntoIterator<Item = IpNetwork>) -> Vec<IpNetwork> {
+    let everything = [
+        IpNetwork::new(Ipv4Addr::UNSPECIFIED.into(), 0).unwrap(),
+        IpNetwork::new(Ipv6Addr::UNSPECIFIED.into(), 0).unwrap(),
+    ];
+
+    subtract(everything, networks)
+}
+
+/// First address and prefix length of the IPv4 and IPv6 networks.
+fn split(networks: impl IntoIterator<Item = IpNetwork>) -> [Vec<(u128, u8)>; 2] {
+    let mut ranges = [Vec::new(), Vec::new()];
+
+    for network in networks {
+        match network.network() {
+            IpAddr::V4(addr) => ranges[0].push((u128::from(addr.to_bits()), network.prefix())),
+            IpAddr::V6(addr) => ranges[1].push((addr.to_bits(), network.prefix())),
+        }
+    }
+
+    ranges
+}
+
+fn join(v4: Vec<(u128, u8)>, v6: Vec<(u128, u8)>) -> Vec<IpNetwork> {
+    let v4 = v4
+        .into_iter()
+        .map(|(start, prefix)| IpNetwork::new(Ipv4Addr::from(start as u32).into(), prefix));
+    let v6 = v6
+        .into_iter()
+        .map(|(start, prefix)| IpNetwork::new(Ipv6Addr::from(start).into(), prefix));
+
+    v4.chain(v6)
+        .map(|network| network.expect("prefix length fits the family"))
+        .collect()
+}
+
+/// Offset of the last address of a network from its first one, the amount of addresses of a
+/// whole IPv6 space does not fit.
+fn span(prefix: u8, bits: u8) -> u128 {
+    u128::MAX
+        .checked_shr(128 - u32::from(bits - prefix))
+        .unwrap_or(0)
+}
+
+/// Whether the range `inner` lies entirely within `outer`.
+fn range_contains(outer: (u128, u8), inner: (u128, u8), bits: u8) -> bool {
+    outer.1 <= inner.1 && inner.0 >= outer.0 && inner.0 - outer.0 <= span(outer.1, bits)
+}
+
+fn aggregate_family(ranges: Vec<(u128, u8)>, bits: u8) -> Vec<(u128, u8)> {
+    let mut stack: Vec<(u128, u8)> = Vec::new();
+
+    for (start, prefix) in ranges.into_iter().sorted() {
+        // sorted by start then by length, a network can only be contained in the last one kept
+        if let Some(&last) = stack.last()
+            && range_contains(last, (start, prefix), bits)
+        {
+            continue;
+        }
+
+        stack.push((start, prefix));
+
+        // merge sibling halves into their parent as long as possible
+        while let [.., (a_start, a_prefix), (b_start, b_prefix)] = stack[..]
+            && a_prefix == b_prefix
+            && a_prefix > 0
+            && a_start & span(a_prefix - 1, bits) == 0
+            && b_start == a_start + span(a_prefix, bits) + 1
+        {
+            stack.truncate(stack.len() - 2);
+            stack.push((a_start, a_prefix - 1));
+        }
+    }
+
+    stack
+}
+
+fn subtract_family(ranges: Vec<(u128, u8)>, removed: &[(u128, u8)], bits: u8) -> Vec<(u128, u8)> {
+    let mut kept = Vec::new();
+    let mut pending = ranges;
+
+    while let Some(range @ (start, prefix)) = pending.pop() {
+        if removed.iter().any(|&r| range_contains(r, range, bits)) {
+            continue;
+        }
+
+        if removed.iter().any(|&r| range_contains(range, r, bits)) {
+            // keep the halves apart until the removed networks can be dropped whole
+            let half = prefix + 1;
+            pending.push((start, half));
+            pending.push((start + span(half, bits) + 1, half));
+        } else {
+            kept.push(range);
+        }
+    }
+
+    aggregate_family(kept, bits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn networks(values: &[&str]) -> Vec<IpNetwork> {
+        values.iter().map(|value| value.parse().unwrap()).collect()
+    }
+
+    fn strings(networks: Vec<IpNetwork>) -> Vec<String> {
+        networks.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn aggregate_merges_adjacent() {
+        let merged = aggregate(networks(&["10.0.1.0/24", "10.0.0.0/24", "10.0.2.0/23"]));
+
+        assert_eq!(strings(merged), ["10.0.0.0/22"]);
+    }
+
+    #[test]
+    fn aggregate_keeps_unaligned_neighbours() {
+        // adjacent but not halves of the same parent
+        let merged = aggregate(networks(&["10.0.1.0/24", "10.0.2.0/24"]));
+
+        assert_eq!(strings(merged), ["10.0.1.0/24", "10.0.2.0/24"]);
+    }
+
+    #[test]
+    fn aggregate_drops_contained() {
+        let merged = aggregate(networks(&["10.0.3.0/24", "10.0.0.0/16", "10.0.0.0/16"]));
+
+        assert_eq!(strings(merged), ["10.0.0.0/16"]);
+    }
+
+    #[test]
+    fn aggregate_keeps_families_apart() {
+        // both start at the zero address, the v6 one would otherwise swallow the v4 one
+        let merged = aggregate(networks(&["::/1", "0.0.0.0/8", "8000::/1", "1.0.0.0/8"]));
+
+        assert_eq!(strings(merged), ["0.0.0.0/7", "::/0"]);
+    }
+
+    #[test]
+    fn subtract_splits() {
+        let left = subtract(networks(&["10.0.0.0/22"]), networks(&["10.0.1.0/24"]));
+
+        assert_eq!(strings(left), ["10.0.0.0/24", "10.0.2.0/23"]);
+    }
+
+    #[test]
+    fn subtract_ignores_other_family() {
+        let left = subtract(networks(&["10.0.0.0/8"]), networks(&["::/0"]));
+
+        assert_eq!(strings(left), ["10.0.0.0/8"]);
+    }
+
+    #[test]
+    fn complement_of_nothing() {
+        assert_eq!(strings(complement([])), ["0.0.0.0/0", "::/0"]);
+    }
+
+    #[test]
+    fn complement_of_everything() {
+        assert!(complement(networks(&["0.0.0.0/0", "::/0"])).is_empty());
+    }
+
+    #[test]
+    fn complement_of_half() {
+        let left = complement(networks(&["0.0.0.0/1", "8000::/1"]));
+
+        assert_eq!(strings(left), ["128.0.0.0/1", "::/1"]);
+    }
+
+    #[test]
+    fn whole_v6_space_contains_last_address() {
+        let last = (u128::MAX, 128);
+
+        assert!(range_contains((0, 0), last, 128));
+        assert!(!range_contains((0, 1), last, 128));
+        assert!(
+            subtract(
+                networks(&["ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff/128"]),
+                networks(&["::/0"])
+            )
+            .is_empty()
+        );
+    }
+}
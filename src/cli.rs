@@ -0,0 +1,44 @@
+use std::path::PathBuf;
+
+#[cfg(target_os = "linux")]
+use crate::daemon::DaemonArgs;
+use crate::import::ImportCommand;
+use crate::measure::MeasureArgs;
+use crate::profiles::ApplyArgs;
+use crate::regiondb::DbCommand;
+use crate::validate::ValidateArgs;
+
+#[derive(clap::Parser)]
+#[command(version, about)]
+pub struct Cli {
+    #[cfg(target_os = "linux")]
+    #[command(flatten)]
+    pub daemon: DaemonArgs,
+
+    /// start recording ping measurements to this file when the window opens
+    #[arg(long)]
+    pub record: Option<PathBuf>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(clap::Subcommand)]
+pub enum Command {
+    /// ping every region for a while and print a latency summary
+    Measure(MeasureArgs),
+
+    /// manage the signed region database
+    #[command(subcommand)]
+    Db(DbCommand),
+
+    /// convert region lists from other sources into a region database
+    #[command(subcommand)]
+    Import(ImportCommand),
+
+    /// check the region list for invalid, duplicated and overlapping prefixes
+    Validate(ValidateArgs),
+
+    /// enable blocking with the regions of a saved profile
+    Apply(ApplyArgs),
+}
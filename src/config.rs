@@ -0,0 +1,94 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result, anyhow};
+use ipnetwork::IpNetwork;
+use serde::{Deserialize, Serialize};
+
+use crate::blocking::BlockMode;
+use crate::prefixes::Region;
+use crate::regions::RegionSorting;
+
+/// Name of the application's directories.
+pub const APP_DIR: &str = "ow2-server-picker";
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Location of the signed region database, updates are disabled when unset.
+    pub region_db_url: Option<String>,
+
+    /// Regions added on top of the region database.
+    pub custom_regions: Vec<Region>,
+
+    /// Changes to the prefixes of regions of the region database, keyed by region key.
+    pub prefix_overrides: BTreeMap<String, PrefixOverride>,
+
+    /// Which addresses are blocked when blocking is enabled.
+    pub block_mode: BlockMode,
+
+    /// Prefixes reachable in allowlist mode, on top of the selected regions and Battle.net.
+    pub allowlist: Vec<IpNetwork>,
+
+    /// Saved selections, keyed by name.
+    pub profiles: BTreeMap<String, Profile>,
+
+    /// Name of the profile applied last.
+    pub active_profile: Option<String>,
+
+    /// Order of the region list.
+    pub sort: RegionSorting,
+
+    /// Language of the interface (ex: `fr-FR`), the system's one when unset.
+    pub locale: Option<String>,
+}
+
+/// A named selection of regions, applied with its own blocking mode.
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Profile {
+    /// Keys of the selected regions.
+    pub regions: BTreeSet<String>,
+
+    pub block_mode: BlockMode,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PrefixOverride {
+    /// Prefixes added to the region.
+    pub extra: Vec<IpNetwork>,
+
+    /// Addresses removed from the prefixes of the region.
+    pub excluded: Vec<IpNetwork>,
+}
+
+impl Config {
+    pub fn path() -> Option<PathBuf> {
+        Some(dirs::config_dir()?.join(APP_DIR).join("config.json"))
+    }
+
+    /// Reads the configuration, falling back to the defaults if there is none yet.
+    pub fn load() -> Result<Self> {
+        let path = Self::path().ok_or_else(|| anyhow!("no configuration directory"))?;
+
+        match fs::read(&path) {
+            Ok(data) => serde_json::from_slice(&data)
+                .with_context(|| format!("invalid configuration in {}", path.display())),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = Self::path().ok_or_else(|| anyhow!("no configuration directory"))?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        fs::write(&path, serde_json::to_vec_pretty(self)?)
+            .with_context(|| format!("failed to write {}", path.display()))
+    }
+}
@@ -0,0 +1,181 @@
+use std::collections::BTreeMap;
+
+use anyhow::{Context, Result, ensure};
+use indexmap::IndexMap;
+use iter_tools::Itertools;
+
+use crate::cidr;
+use crate::config::{Config, PrefixOverride};
+use crate::i18n::tr;
+use crate::prefixes::{self, Continent, Region};
+use crate::validate::{self, Issue};
+
+/// Prefix of the keys of custom regions.
+const CUSTOM_KEY_PREFIX: &str = "custom/";
+
+/// Custom region being edited, its fields are only parsed when saving.
+#[derive(Default)]
+pub struct RegionDraft {
+    pub name: String,
+    pub code: String,
+    pub ping: String,
+    pub ping6: String,
+    pub continent: Continent,
+    pub prefixes: String,
+}
+
+/// Prefix override being edited.
+#[derive(Default)]
+pub struct OverrideDraft {
+    pub extra: String,
+    pub excluded: String,
+}
+
+/// Editor of the custom regions and prefix overrides of the configuration.
+#[derive(Default)]
+pub struct CustomEditor {
+    /// Whether the editor window is shown.
+    pub open: bool,
+
+    pub regions: Vec<RegionDraft>,
+
+    /// Overrides keyed by region key.
+    pub overrides: IndexMap<String, OverrideDraft>,
+
+    /// Regions of the region database, the overrides apply to them.
+    pub published: Vec<Region>,
+
+    /// Errors preventing the last save.
+    pub errors: Vec<String>,
+
+    /// Warnings about the saved customizations.
+    pub warnings: Vec<String>,
+}
+
+impl RegionDraft {
+    fn from_region(region: &Region) -> Self {
+        Self {
+            name: region.name.clone(),
+            code: region.code.clone(),
+            ping: region.ping.to_string(),
+            ping6: region
+                .ping6
+                .map(|addr| addr.to_string())
+                .unwrap_or_default(),
+            continent: region.continent,
+            prefixes: region.prefixes.iter().join("\n"),
+        }
+    }
+
+    fn parse(&self) -> Result<Region> {
+        let name = self.name.trim();
+        let code = self.code.trim();
+        ensure!(!name.is_empty(), tr!("custom-no-name"));
+        ensure!(!code.is_empty(), tr!("custom-no-code", name = name));
+
+        let ping = self
+            .ping
+            .trim()
+            .parse()
+            .with_context(|| tr!("custom-invalid-ping", name = name))?;
+        let ping6 = match self.ping6.trim() {
+            "" => None,
+            addr => Some(
+                addr.parse()
+                    .with_context(|| tr!("custom-invalid-ping6", name = name))?,
+            ),
+        };
+        let prefixes = cidr::parse_list(&self.prefixes)
+            .with_context(|| tr!("custom-invalid-prefixes", name = name))?;
+        ensure!(!prefixes.is_empty(), tr!("custom-no-prefixes", name = name));
+
+        Ok(Region {
+            key: format!("{CUSTOM_KEY_PREFIX}{}", code.to_lowercase()),
+            name: name.to_string(),
+            code: code.to_string(),
+            ping,
+            ping6,
+            continent: self.continent,
+            prefixes,
+        })
+    }
+}
+
+impl CustomEditor {
+    /// Opens the editor with the customizations of the configuration.
+    pub fn open(&mut self, config: &Config) {
+        self.regions = config
+            .custom_regions
+            .iter()
+            .map(RegionDraft::from_region)
+            .collect();
+        self.overrides = config
+            .prefix_overrides
+            .iter()
+            .map(|(key, o)| {
+                (
+                    key.clone(),
+                    OverrideDraft {
+                        extra: o.extra.iter().join("\n"),
+                        excluded: o.excluded.iter().join("\n"),
+                    },
+                )
+            })
+            .collect();
+        self.published = prefixes::published();
+        self.errors.clear();
+        self.warnings.clear();
+        self.open = true;
+    }
+
+    /// Validates the drafts and writes them to `config`.
+    ///
+    /// Nothing is written when there are errors, they are kept in [`Self::errors`].
+    pub fn apply(&mut self, config: &mut Config) -> bool {
+        self.errors.clear();
+        self.warnings.clear();
+
+        let regions = self
+            .regions
+            .iter()
+            .map(RegionDraft::parse)
+            .filter_map(|r| r.map_err(|e| self.errors.push(format!("{e:#}"))).ok())
+            .collect_vec();
+
+        for key in regions.iter().map(|r| &r.key).duplicates() {
+            self.errors
+                .push(tr!("custom-duplicate-key", key = key.as_str()));
+        }
+
+        let mut overrides = BTreeMap::new();
+        for (key, draft) in &self.overrides {
+            if draft.extra.trim().is_empty() && draft.excluded.trim().is_empty() {
+                continue;
+            }
+
+            let parsed = cidr::parse_list(&draft.extra)
+                .and_then(|extra| Ok((extra, cidr::parse_list(&draft.excluded)?)))
+                .with_context(|| tr!("custom-invalid-override", key = key.as_str()));
+
+            match parsed {
+                Ok((extra, excluded)) => {
+                    overrides.insert(key.clone(), PrefixOverride { extra, excluded });
+                }
+                Err(e) => self.errors.push(format!("{e:#}")),
+            }
+        }
+
+        if !self.errors.is_empty() {
+            return false;
+        }
+
+        config.custom_regions = regions;
+        config.prefix_overrides = overrides;
+        let mut regions = self.published.clone();
+        self.warnings = prefixes::customize(&mut regions, config);
+        self.warnings
+            .extend(validate::validate(&regions).iter().map(Issue::to_string));
+
+        true
+    }
+}
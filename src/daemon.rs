@@ -3,15 +3,17 @@ use std::io;
 use std::result;
 
 use anyhow::Result;
-use iter_tools::Itertools;
 
-use crate::{fw, prefixes};
+use crate::blocking::BlockRule;
+#[cfg(target_os = "windows")]
+use crate::cidr;
+use crate::fw;
 
 #[cfg(target_os = "linux")]
-#[derive(clap::Parser)]
-pub struct Cli {
+#[derive(clap::Args)]
+pub struct DaemonArgs {
     /// run as a daemon to add ow2 processes to the proper cgroup
-    #[arg(long)]
+    #[arg(long, requires = "game_path")]
     pub daemon: bool,
 
     /// kill the running daemon
@@ -19,25 +21,16 @@ pub struct Cli {
     pub kill: bool,
 
     #[arg(long)]
-    pub game_path: String,
+    pub game_path: Option<String>,
 
-    /// prefixes to block in daemon mode (ex: 10.0.0.0/24)
-    pub prefixes: Vec<String>,
+    /// prefixes to block in daemon mode, optionally with their region (ex: blizzard/ord1=10.0.0.0/24)
+    pub prefixes: Vec<BlockRule>,
 }
 
 #[cfg(target_os = "linux")]
 #[tokio::main]
-pub async fn daemon_main(cli: Cli) -> Result<()> {
-    let prefixes = prefixes::load();
-    fw::start(
-        prefixes
-            .iter()
-            .filter(|v| cli.prefixes.contains(&v.key))
-            .flat_map(|v| v.prefixes.clone())
-            .collect_vec(),
-        cli.game_path,
-    )
-    .await
+pub async fn daemon_main(args: DaemonArgs) -> Result<()> {
+    fw::start(args.prefixes, args.game_path.unwrap_or_default()).await
 }
 
 #[cfg(target_os = "windows")]
@@ -45,23 +38,85 @@ pub fn kill() -> Result<(), KillError> {
     Ok(fw::stop()?)
 }
 
+/// Stops the running daemon, returning once its rules are removed.
 #[cfg(target_os = "linux")]
 pub fn kill() -> result::Result<(), KillError> {
+    use std::io::{Read, Write};
+    use std::net::Shutdown;
+    use std::time::Duration;
+
+    let mut stream = connect()?;
+    stream.write_all(fw::KILL_MSG)?;
+    stream.shutdown(Shutdown::Write)?;
+
+    // the daemon closes the connection after removing its rules
+    stream.set_read_timeout(Some(Duration::from_secs(10)))?;
+    stream.read_to_end(&mut Vec::new())?;
+
+    Ok(())
+}
+
+/// Asks the running daemon for the connections of the game it observed.
+#[cfg(target_os = "linux")]
+pub fn connections() -> result::Result<Vec<fw::ObservedConnection>, KillError> {
+    request(fw::CONNECTIONS_MSG)
+}
+
+/// Asks the running daemon for the game processes it moved into the cgroup.
+#[cfg(target_os = "linux")]
+pub fn processes() -> result::Result<Vec<i32>, KillError> {
+    request(fw::PROCESSES_MSG)
+}
+
+/// Waits until the daemon started by [`start`] answers, failing when it exits before.
+///
+/// `pkexec` refuses to authorize a process whose parent exited, so callers exiting right after
+/// starting the daemon must wait for it.
+#[cfg(target_os = "linux")]
+pub fn wait_until_running(daemon: &mut std::process::Child) -> Result<()> {
+    loop {
+        if let Some(status) = daemon.try_wait()? {
+            anyhow::bail!("the daemon exited before blocking ({status})");
+        }
+
+        if processes().is_ok() {
+            return Ok(());
+        }
+
+        std::thread::sleep(std::time::Duration::from_millis(250));
+    }
+}
+
+/// Asks the running daemon for the traffic dropped by each rule.
+#[cfg(target_os = "linux")]
+pub fn rule_counters() -> result::Result<Vec<fw::RuleCounter>, KillError> {
+    request(fw::COUNTERS_MSG)
+}
+
+#[cfg(target_os = "linux")]
+fn request<T: serde::de::DeserializeOwned>(msg: &[u8]) -> result::Result<T, KillError> {
     use std::io::Write;
+    use std::net::Shutdown;
+
+    let mut stream = connect()?;
+    stream.write_all(msg)?;
+    stream.shutdown(Shutdown::Write)?;
+
+    serde_json::from_reader(stream).map_err(|e| KillError::Anyhow(e.into()))
+}
+
+#[cfg(target_os = "linux")]
+fn connect() -> result::Result<std::os::unix::net::UnixStream, KillError> {
     use std::os::linux::net::SocketAddrExt;
     use std::os::unix::net::{SocketAddr, UnixStream};
 
-    let mut stream = UnixStream::connect_addr(&SocketAddr::from_abstract_name(fw::SOCKET_NAME)?)
-        .map_err(|e| {
-            if e.kind() == io::ErrorKind::ConnectionRefused {
-                KillError::Refused
-            } else {
-                KillError::IoError(e)
-            }
-        })?;
-    stream.write_all(b"kill")?;
-
-    Ok(())
+    UnixStream::connect_addr(&SocketAddr::from_abstract_name(fw::SOCKET_NAME)?).map_err(|e| {
+        if e.kind() == io::ErrorKind::ConnectionRefused {
+            KillError::Refused
+        } else {
+            KillError::IoError(e)
+        }
+    })
 }
 
 #[allow(dead_code)]
@@ -77,15 +132,9 @@ pub enum KillError {
 
 #[cfg(target_os = "windows")]
 #[tokio::main]
-pub async fn start(block_list: impl Iterator<Item = String>, game_path: String) -> Result<()> {
-    let block_list = block_list.collect_vec();
-    let all_prefixes = prefixes::load();
+pub async fn start(blocks: Vec<BlockRule>, game_path: String) -> Result<()> {
     fw::start(
-        all_prefixes
-            .iter()
-            .filter(|&v| block_list.contains(&v.key))
-            .flat_map(|v| v.prefixes.clone())
-            .collect_vec(),
+        cidr::aggregate(blocks.into_iter().map(|rule| rule.prefix)),
         game_path,
     )
     .await?;
@@ -93,17 +142,17 @@ pub async fn start(block_list: impl Iterator<Item = String>, game_path: String)
     Ok(())
 }
 
+/// Starts the daemon through `pkexec`, the returned process exits along with the daemon.
 #[cfg(target_os = "linux")]
-pub fn start(block_list: impl Iterator<Item = String>, game_path: String) -> Result<()> {
-    let block_list = block_list.collect_vec();
-    std::process::Command::new("/usr/bin/env")
+pub fn start(blocks: Vec<BlockRule>, game_path: String) -> Result<std::process::Child> {
+    let daemon = std::process::Command::new("/usr/bin/env")
         .arg("pkexec")
         .arg(std::env::current_exe()?)
         .arg("--daemon")
         .arg("--game-path")
         .arg(game_path)
-        .args(block_list)
+        .args(blocks.iter().map(ToString::to_string))
         .spawn()?;
 
-    Ok(())
+    Ok(daemon)
 }
@@ -1,58 +1,194 @@
-use std::collections::HashSet;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::ffi::{CStr, c_int};
-use std::fs;
-use std::io::Read;
 use std::os::linux::net::SocketAddrExt;
 use std::os::unix::net::{SocketAddr, UnixListener};
-use std::time::Duration;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+use std::{fs, net};
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use ipnetwork::IpNetwork;
 use iter_tools::Itertools;
 use libc::{NFPROTO_IPV4, NFPROTO_IPV6};
 use nftnl::expr::ToSlice;
 use nftnl::*;
+use serde::{Deserialize, Serialize};
 
 mod cgroup;
+mod conntrack;
+mod counters;
+mod netlink;
 use cgroup::CGroup;
-use tokio::sync::watch;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::UnixStream;
+use tokio::signal::unix::{SignalKind, signal};
+use tokio::sync::mpsc;
+
+use crate::blocking::BlockRule;
 
 pub const SOCKET_NAME: &str = "ow2serverpicker";
 
-pub async fn start(blocks: Vec<IpNetwork>, game_path: String) -> Result<()> {
-    create_tables_impl(blocks)?;
+/// Message stopping the daemon.
+pub const KILL_MSG: &[u8; 4] = b"kill";
+
+/// Message asking the daemon for the game's connections, answered with a JSON array of
+/// [`ObservedConnection`].
+pub const CONNECTIONS_MSG: &[u8; 4] = b"conn";
+
+/// Message asking the daemon for the counters of the blocking rules, answered with a JSON
+/// array of [`RuleCounter`].
+pub const COUNTERS_MSG: &[u8; 4] = b"hits";
+
+/// Message asking the daemon for the game processes it moved into the cgroup, answered with a
+/// JSON array of process ids.
+pub const PROCESSES_MSG: &[u8; 4] = b"pids";
+
+/// Longest a client may take to send its message.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Name of the nftables tables holding the rules.
+const TABLE_NAME: &CStr = c"ow2serverpicker";
+
+/// Conntrack mark bit of the game's flows, set by the first rule of the output chains.
+///
+/// The other bits are left to other tools (ex: VPN split tunnelling, policy routing).
+const CONNECTION_MARK: u32 = 1 << 28;
+
+/// A remote UDP endpoint the game talks to, kept while conntrack tracks the flow.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ObservedConnection {
+    pub remote: net::SocketAddr,
+    pub first_seen: SystemTime,
+    pub last_seen: SystemTime,
+}
+
+/// Traffic dropped by the rule blocking a prefix.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RuleCounter {
+    pub prefix: IpNetwork,
+    /// Key of the region the prefix is blocked for, `None` for addresses of no region.
+    #[serde(default)]
+    pub region: Option<String>,
+    pub packets: u64,
+    pub bytes: u64,
+}
+
+/// Blocks the prefixes for the game until killed, removing the rules when exiting.
+pub async fn start(blocks: Vec<BlockRule>, game_path: String) -> Result<()> {
+    // bound before touching the rules, so that a second daemon leaves the running one alone
+    let listener = UnixListener::bind_addr(&SocketAddr::from_abstract_name(SOCKET_NAME)?)?;
+    listener.set_nonblocking(true)?;
+    let listener = Arc::new(tokio::net::UnixListener::from_std(listener)?);
+
+    let result = block(blocks, game_path, listener.clone()).await;
+
+    // the game stays in the cgroup, leftover rules would keep blocking it
+    stop()?;
+
+    // the socket is released and the killers see their connection end only now, a daemon started
+    // right after could otherwise have its rules deleted by the `stop` above
+    drop(listener);
+    result.map(drop)
+}
+
+/// Runs until killed, returning the connections of the kill requests to close once cleaned up.
+async fn block(
+    blocks: Vec<BlockRule>,
+    game_path: String,
+    listener: Arc<tokio::net::UnixListener>,
+) -> Result<Vec<UnixStream>> {
+    create_tables_impl(blocks.iter().map(|rule| rule.prefix).collect())?;
+
+    let mut regions = HashMap::new();
+    for rule in blocks {
+        regions.entry(rule.prefix).or_insert(rule.region);
+    }
+
+    let mut terminate = signal(SignalKind::terminate())?;
+    let mut interrupt = signal(SignalKind::interrupt())?;
+    let mut hangup = signal(SignalKind::hangup())?;
 
     let cgroup = CGroup::new()?;
     let mut pids = HashSet::new();
-    let (update_killed, killed) = watch::channel(false);
+    let (killer_tx, mut killers) = mpsc::unbounded_channel();
+    let observed = Arc::new(Mutex::new(BTreeMap::new()));
+    let moved = Arc::new(Mutex::new(BTreeSet::new()));
     let runtime = tokio::runtime::Handle::current();
 
-    let handle: tokio::task::JoinHandle<Result<()>> = runtime.spawn(async move {
-        let addr = SocketAddr::from_abstract_name(SOCKET_NAME)?;
-        let listener = UnixListener::bind_addr(&addr)?;
-        let (mut conn, _) = listener.accept()?;
+    let mut handle: tokio::task::JoinHandle<Result<()>> = runtime.spawn({
+        let observed = observed.clone();
+        let moved = moved.clone();
 
-        const MSG: &[u8] = "kill".as_bytes();
-        let mut buf = [0u8; MSG.len()];
-        conn.read_exact(&mut buf)?;
+        async move {
+            loop {
+                let (mut conn, _) = listener.accept().await?;
 
-        if buf == MSG {
-            update_killed.send_replace(true);
-        }
+                let mut buf = [0u8; KILL_MSG.len()];
+                let request = tokio::time::timeout(REQUEST_TIMEOUT, conn.read_exact(&mut buf));
+                if !matches!(request.await, Ok(Ok(_))) {
+                    continue;
+                }
 
-        Ok(())
+                let answer = match &buf {
+                    KILL_MSG => {
+                        killer_tx.send(conn).ok();
+                        continue;
+                    }
+                    CONNECTIONS_MSG => {
+                        let connections = observed
+                            .lock()
+                            .unwrap()
+                            .values()
+                            .cloned()
+                            .collect::<Vec<ObservedConnection>>();
+                        serde_json::to_vec(&connections)?
+                    }
+                    COUNTERS_MSG => match counters::rule_counters(TABLE_NAME) {
+                        Ok(mut counters) => {
+                            for counter in &mut counters {
+                                counter.region = regions.get(&counter.prefix).cloned().flatten();
+                            }
+                            serde_json::to_vec(&counters)?
+                        }
+                        Err(e) => {
+                            eprintln!("failed to read the rule counters: {e:#}");
+                            continue;
+                        }
+                    },
+                    PROCESSES_MSG => {
+                        let moved = moved.lock().unwrap().iter().copied().collect::<Vec<i32>>();
+                        serde_json::to_vec(&moved)?
+                    }
+                    _ => continue,
+                };
+
+                conn.write_all(&answer).await.ok();
+            }
+        }
     });
 
+    let mut killed = Vec::new();
+
     loop {
-        tokio::time::sleep(Duration::from_millis(1000)).await;
+        tokio::select! {
+            _ = tokio::time::sleep(Duration::from_millis(1000)) => {}
+            Some(killer) = killers.recv() => {
+                killed.push(killer);
+                break;
+            }
+            _ = terminate.recv() => break,
+            _ = interrupt.recv() => break,
+            _ = hangup.recv() => break,
+        }
 
+        // the listener only stops on errors, kill requests end the loop above
         if handle.is_finished() {
-            eprintln!("{:#?}", handle.await);
-            return Ok(());
+            (&mut handle).await?.context("the control socket failed")?;
+            break;
         }
 
-        if *killed.borrow() {
-            break;
+        if !pids.is_empty() {
+            observe_connections(&observed);
         }
 
         let Ok(proc_dirs) = fs::read_dir("/proc") else {
@@ -72,8 +208,11 @@ pub async fn start(blocks: Vec<IpNetwork>, game_path: String) -> Result<()> {
         let new_pids = new_pids.difference(&pids).cloned().collect_vec();
         for pid in new_pids {
             pids.insert(pid);
-            if let Err(e) = cgroup.add(pid) {
-                eprintln!("{e:#?}");
+            match cgroup.add(pid) {
+                Ok(()) => {
+                    moved.lock().unwrap().insert(pid);
+                }
+                Err(e) => eprintln!("{e:#?}"),
             }
         }
     }
@@ -81,11 +220,48 @@ pub async fn start(blocks: Vec<IpNetwork>, game_path: String) -> Result<()> {
     handle.abort();
     handle.await.ok();
 
-    Ok(())
+    while let Ok(killer) = killers.try_recv() {
+        killed.push(killer);
+    }
+
+    Ok(killed)
+}
+
+/// Records the remote endpoints of the game's UDP flows, forgetting the flows that ended.
+fn observe_connections(observed: &Mutex<BTreeMap<net::SocketAddr, ObservedConnection>>) {
+    // dumped before locking, the listener answers from the map meanwhile
+    let destinations = match conntrack::udp_destinations(CONNECTION_MARK) {
+        Ok(destinations) => destinations,
+        Err(e) => {
+            eprintln!("failed to dump conntrack: {e:#}");
+            return;
+        }
+    };
+
+    let now = SystemTime::now();
+    let mut observed = observed.lock().unwrap();
+    observed.retain(|remote, _| destinations.contains(remote));
+
+    for remote in destinations {
+        // name resolution is not game traffic
+        if remote.port() == 53 {
+            continue;
+        }
+
+        observed
+            .entry(remote)
+            .and_modify(|connection| connection.last_seen = now)
+            .or_insert(ObservedConnection {
+                remote,
+                first_seen: now,
+                last_seen: now,
+            });
+    }
 }
 
 fn create_tables_impl(blocks: Vec<IpNetwork>) -> Result<()> {
     stop()?;
+    create_mark_rules()?;
 
     for chunk in blocks.chunks(50) {
         create_table(chunk)?;
@@ -96,10 +272,10 @@ fn create_tables_impl(blocks: Vec<IpNetwork>) -> Result<()> {
 fn create_table(blocks: &[IpNetwork]) -> Result<()> {
     let mut batch4 = Batch::new();
     let mut batch6 = Batch::new();
-    let table4 = Table::new(&c"ow2serverpicker", nftnl::ProtoFamily::Ipv4);
+    let table4 = Table::new(&TABLE_NAME, nftnl::ProtoFamily::Ipv4);
     batch4.add(&table4, MsgType::Add);
 
-    let table6 = Table::new(&c"ow2serverpicker", nftnl::ProtoFamily::Ipv6);
+    let table6 = Table::new(&TABLE_NAME, nftnl::ProtoFamily::Ipv6);
     batch6.add(&table6, MsgType::Add);
 
     let mut chain4 = Chain::new(&c"output", &table4);
@@ -140,6 +316,37 @@ fn create_table(blocks: &[IpNetwork]) -> Result<()> {
     Ok(())
 }
 
+/// Marks the game's flows so that [`conntrack`] can find them, even when nothing is blocked.
+fn create_mark_rules() -> Result<()> {
+    for family in [nftnl::ProtoFamily::Ipv4, nftnl::ProtoFamily::Ipv6] {
+        let mut batch = Batch::new();
+        let table = Table::new(&TABLE_NAME, family);
+        batch.add(&table, MsgType::Add);
+
+        let mut chain = Chain::new(&c"output", &table);
+        chain.set_hook(Hook::Out, 500);
+        chain.set_policy(Policy::Accept);
+        chain.set_type(ChainType::Filter);
+        batch.add(&chain, MsgType::Add);
+
+        let mut rule = Rule::new(&chain);
+        rule.add_expr(&nft_expr!(meta cgroup));
+        rule.add_expr(&nft_expr!(cmp == cgroup::NET_CLS_CLASSID));
+        // ct mark set ct mark or CONNECTION_MARK
+        rule.add_expr(&nft_expr!(ct mark));
+        rule.add_expr(&nftnl::expr::Bitwise::new(
+            !CONNECTION_MARK,
+            CONNECTION_MARK,
+        ));
+        rule.add_expr(&nft_expr!(ct mark set));
+        batch.add(&rule, MsgType::Add);
+
+        send(&batch.finalize())?;
+    }
+
+    Ok(())
+}
+
 fn create_rule<'a>(
     chain: &'a Chain<'a>,
     batch: &'a mut Batch,
@@ -159,13 +366,14 @@ fn create_rule<'a>(
         vec![0u8; mask.to_slice().len()].as_slice(),
     ));
     rule.add_expr(&nftnl::expr::Cmp::new(nftnl::expr::CmpOp::Eq, addr));
+    rule.add_expr(&nft_expr!(counter));
     rule.add_expr(&nft_expr!(verdict drop));
     batch.add(&rule, MsgType::Add);
 }
 
 pub fn stop() -> Result<()> {
-    delete_table(&c"ow2serverpicker", nftnl::ProtoFamily::Ipv4)?;
-    delete_table(&c"ow2serverpicker", nftnl::ProtoFamily::Ipv6)?;
+    delete_table(&TABLE_NAME, nftnl::ProtoFamily::Ipv4)?;
+    delete_table(&TABLE_NAME, nftnl::ProtoFamily::Ipv6)?;
     Ok(())
 }
 
@@ -0,0 +1,61 @@
+//! Minimal ctnetlink client, dumps the conntrack table to find the game's connections.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+use anyhow::Result;
+use libc::IPPROTO_UDP;
+
+use super::netlink::{self, find_attr};
+
+const NFNL_SUBSYS_CTNETLINK: u16 = 1;
+const IPCTNL_MSG_CT_GET: u16 = 1;
+
+const CTA_TUPLE_ORIG: u16 = 1;
+const CTA_MARK: u16 = 8;
+const CTA_TUPLE_IP: u16 = 1;
+const CTA_TUPLE_PROTO: u16 = 2;
+const CTA_IP_V4_DST: u16 = 2;
+const CTA_IP_V6_DST: u16 = 4;
+const CTA_PROTO_NUM: u16 = 1;
+const CTA_PROTO_DST_PORT: u16 = 3;
+
+/// Remote endpoints of the UDP flows whose mark has the bits of `mark` set.
+pub fn udp_destinations(mark: u32) -> Result<Vec<SocketAddr>> {
+    let mut destinations = Vec::new();
+
+    netlink::dump(
+        NFNL_SUBSYS_CTNETLINK,
+        IPCTNL_MSG_CT_GET,
+        libc::AF_UNSPEC as u8,
+        &[],
+        |attrs| destinations.extend(parse_flow(attrs, mark)),
+    )?;
+
+    Ok(destinations)
+}
+
+/// Extracts the original destination of a UDP flow whose mark has the bits of `mark` set.
+fn parse_flow(attrs: &[u8], mark: u32) -> Option<SocketAddr> {
+    let flow_mark = find_attr(attrs, CTA_MARK)?;
+    if u32::from_be_bytes(flow_mark.try_into().ok()?) & mark != mark {
+        return None;
+    }
+
+    let tuple = find_attr(attrs, CTA_TUPLE_ORIG)?;
+
+    let proto = find_attr(tuple, CTA_TUPLE_PROTO)?;
+    if *find_attr(proto, CTA_PROTO_NUM)?.first()? != IPPROTO_UDP as u8 {
+        return None;
+    }
+    let port = u16::from_be_bytes(find_attr(proto, CTA_PROTO_DST_PORT)?.try_into().ok()?);
+
+    let ip = find_attr(tuple, CTA_TUPLE_IP)?;
+    let addr = if let Some(addr) = find_attr(ip, CTA_IP_V4_DST) {
+        IpAddr::from(Ipv4Addr::from(<[u8; 4]>::try_from(addr).ok()?))
+    } else {
+        let addr = find_attr(ip, CTA_IP_V6_DST)?;
+        IpAddr::from(Ipv6Addr::from(<[u8; 16]>::try_from(addr).ok()?))
+    };
+
+    Some(SocketAddr::new(addr, port))
+}
@@ -0,0 +1,87 @@
+//! Reads back the counters of the blocking rules.
+
+use std::ffi::CStr;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use anyhow::Result;
+use ipnetwork::IpNetwork;
+
+use super::RuleCounter;
+use super::netlink::{self, find_attr, iter_attrs};
+
+const NFNL_SUBSYS_NFTABLES: u16 = 10;
+const NFT_MSG_GETRULE: u16 = 7;
+
+const NFTA_RULE_TABLE: u16 = 1;
+const NFTA_RULE_EXPRESSIONS: u16 = 4;
+const NFTA_EXPR_NAME: u16 = 1;
+const NFTA_EXPR_DATA: u16 = 2;
+const NFTA_BITWISE_MASK: u16 = 4;
+const NFTA_CMP_DATA: u16 = 3;
+const NFTA_DATA_VALUE: u16 = 1;
+const NFTA_COUNTER_BYTES: u16 = 1;
+const NFTA_COUNTER_PACKETS: u16 = 2;
+
+/// Counters of the rules of `table` dropping a prefix.
+pub fn rule_counters(table: &CStr) -> Result<Vec<RuleCounter>> {
+    let mut counters = Vec::new();
+
+    netlink::dump(
+        NFNL_SUBSYS_NFTABLES,
+        NFT_MSG_GETRULE,
+        libc::NFPROTO_UNSPEC as u8,
+        &[(NFTA_RULE_TABLE, table.to_bytes_with_nul())],
+        |attrs| counters.extend(parse_rule(attrs, table)),
+    )?;
+
+    Ok(counters)
+}
+
+/// Rebuilds the prefix of a blocking rule from its mask and compared address.
+fn parse_rule(attrs: &[u8], table: &CStr) -> Option<RuleCounter> {
+    if find_attr(attrs, NFTA_RULE_TABLE)? != table.to_bytes_with_nul() {
+        return None;
+    }
+
+    let mut mask = None;
+    let mut addr = None;
+    let mut counter = None;
+
+    for (_, expr) in iter_attrs(find_attr(attrs, NFTA_RULE_EXPRESSIONS)?) {
+        let data = find_attr(expr, NFTA_EXPR_DATA)?;
+
+        match find_attr(expr, NFTA_EXPR_NAME)? {
+            b"bitwise\0" => {
+                mask =
+                    find_attr(data, NFTA_BITWISE_MASK).and_then(|v| find_attr(v, NFTA_DATA_VALUE))
+            }
+            // the address is compared last, after the cgroup and protocol
+            b"cmp\0" => {
+                addr = find_attr(data, NFTA_CMP_DATA).and_then(|v| find_attr(v, NFTA_DATA_VALUE))
+            }
+            b"counter\0" => {
+                let packets = find_attr(data, NFTA_COUNTER_PACKETS)?;
+                let bytes = find_attr(data, NFTA_COUNTER_BYTES)?;
+                counter = Some((
+                    u64::from_be_bytes(packets.try_into().ok()?),
+                    u64::from_be_bytes(bytes.try_into().ok()?),
+                ));
+            }
+            _ => {}
+        }
+    }
+
+    let (packets, bytes) = counter?;
+    let prefix = mask?.iter().map(|byte| byte.count_ones()).sum::<u32>() as u8;
+    let addr = match addr? {
+        &[a, b, c, d] => IpAddr::from(Ipv4Addr::new(a, b, c, d)),
+        addr => IpAddr::from(Ipv6Addr::from(<[u8; 16]>::try_from(addr).ok()?)),
+    };
+
+    Some(RuleCounter {
+        prefix: IpNetwork::new(addr, prefix).ok()?,
+        region: None,
+        packets,
+        bytes,
+    })
+}
@@ -0,0 +1,121 @@
+//! Helpers to send netfilter dump requests and walk their attributes.
+
+use std::io;
+
+use anyhow::{Result, bail};
+use libc::{NLM_F_DUMP, NLM_F_REQUEST, NLMSG_DONE, NLMSG_ERROR};
+
+const NFNETLINK_V0: u8 = 0;
+
+/// Strips the nested and byte order flags of an attribute type.
+const NLA_TYPE_MASK: u16 = 0x3fff;
+
+const NLMSG_HDRLEN: usize = 16;
+const NFGENMSG_LEN: usize = 4;
+
+/// Sends a dump request and calls `on_message` with the attributes of every answer.
+pub fn dump(
+    subsys: u16,
+    msg: u16,
+    family: u8,
+    attrs: &[(u16, &[u8])],
+    mut on_message: impl FnMut(&[u8]),
+) -> Result<()> {
+    let socket = mnl::Socket::new(mnl::Bus::Netfilter)?;
+    socket.send(&dump_request(subsys, msg, family, attrs))?;
+
+    let portid = socket.portid();
+    let mut buffer = vec![0; 1 << 16];
+
+    loop {
+        let len = socket.recv(&mut buffer)?;
+        if len == 0 {
+            return Ok(());
+        }
+
+        let mut messages = &buffer[..len];
+        while messages.len() >= NLMSG_HDRLEN {
+            let msg_len = u32::from_ne_bytes(messages[0..4].try_into().unwrap()) as usize;
+            let msg_type = u16::from_ne_bytes(messages[4..6].try_into().unwrap());
+            let msg_portid = u32::from_ne_bytes(messages[12..16].try_into().unwrap());
+            if msg_len < NLMSG_HDRLEN || msg_len > messages.len() {
+                bail!("truncated netlink message");
+            }
+
+            let payload = &messages[NLMSG_HDRLEN..msg_len];
+            messages = &messages[align(msg_len).min(messages.len())..];
+
+            if msg_portid != portid {
+                continue;
+            }
+
+            match i32::from(msg_type) {
+                NLMSG_DONE => return Ok(()),
+                NLMSG_ERROR => {
+                    let errno = i32::from_ne_bytes(payload[0..4].try_into().unwrap());
+                    if errno != 0 {
+                        return Err(io::Error::from_raw_os_error(-errno).into());
+                    }
+                }
+                _ => on_message(payload.get(NFGENMSG_LEN..).unwrap_or_default()),
+            }
+        }
+    }
+}
+
+fn dump_request(subsys: u16, msg: u16, family: u8, attrs: &[(u16, &[u8])]) -> Vec<u8> {
+    let msg_type = (subsys << 8) | msg;
+    let flags = (NLM_F_REQUEST | NLM_F_DUMP) as u16;
+
+    let mut request = Vec::new();
+    request.extend(0u32.to_ne_bytes()); // length, set below
+    request.extend(msg_type.to_ne_bytes());
+    request.extend(flags.to_ne_bytes());
+    request.extend(0u32.to_ne_bytes()); // sequence
+    request.extend(0u32.to_ne_bytes()); // port id, filled by the kernel
+
+    // nfgenmsg: family, version, resource id
+    request.extend([family, NFNETLINK_V0, 0, 0]);
+
+    for (attr_type, value) in attrs {
+        let len = 4 + value.len() as u16;
+        request.extend(len.to_ne_bytes());
+        request.extend(attr_type.to_ne_bytes());
+        request.extend(*value);
+        request.resize(align(request.len()), 0);
+    }
+
+    let len = request.len() as u32;
+    request[0..4].copy_from_slice(&len.to_ne_bytes());
+
+    request
+}
+
+/// Payload of the first attribute of type `attr_type`.
+pub fn find_attr(attrs: &[u8], attr_type: u16) -> Option<&[u8]> {
+    iter_attrs(attrs).find_map(|(ty, value)| (ty == attr_type).then_some(value))
+}
+
+/// Types and payloads of consecutive attributes.
+pub fn iter_attrs(mut attrs: &[u8]) -> impl Iterator<Item = (u16, &[u8])> {
+    std::iter::from_fn(move || {
+        if attrs.len() < 4 {
+            return None;
+        }
+
+        let len = u16::from_ne_bytes([attrs[0], attrs[1]]) as usize;
+        let ty = u16::from_ne_bytes([attrs[2], attrs[3]]) & NLA_TYPE_MASK;
+        if len < 4 || len > attrs.len() {
+            return None;
+        }
+
+        let value = &attrs[4..len];
+        attrs = &attrs[align(len).min(attrs.len())..];
+
+        Some((ty, value))
+    })
+}
+
+fn align(len: usize) -> usize {
+    (len + 3) & !3
+}
@@ -0,0 +1,112 @@
+//! Translations of the user interface, stored as Fluent files in `locales/`.
+
+use std::sync::OnceLock;
+
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource};
+use unic_langid::LanguageIdentifier;
+
+use crate::prefixes::Region;
+
+/// Bundled translations, the first one is used for missing messages.
+const LOCALES: &[(&str, &str)] = &[
+    ("en-US", include_str!("../locales/en-US/main.ftl")),
+    ("fr-FR", include_str!("../locales/fr-FR/main.ftl")),
+];
+
+static LOCALIZER: OnceLock<Localizer> = OnceLock::new();
+
+struct Localizer {
+    /// Bundles in order of preference, ending with the fallback.
+    bundles: Vec<FluentBundle<FluentResource>>,
+}
+
+impl Localizer {
+    fn new(requested: Option<&str>) -> Self {
+        let requested = requested
+            .map(ToString::to_string)
+            .or_else(sys_locale::get_locale)
+            .and_then(|locale| locale.replace('_', "-").parse::<LanguageIdentifier>().ok());
+
+        let preferred = requested.and_then(|requested| {
+            LOCALES
+                .iter()
+                .map(|(id, _)| id.parse::<LanguageIdentifier>().unwrap())
+                .position(|id| id.language == requested.language)
+        });
+
+        let bundles = preferred
+            .into_iter()
+            .filter(|&i| i != 0)
+            .chain([0])
+            .map(|i| bundle(LOCALES[i].0, LOCALES[i].1))
+            .collect();
+
+        Self { bundles }
+    }
+
+    fn format(&self, id: &str, args: Option<&FluentArgs>) -> Option<String> {
+        self.bundles.iter().find_map(|bundle| {
+            let pattern = bundle.get_message(id)?.value()?;
+            let mut errors = Vec::new();
+            let text = bundle.format_pattern(pattern, args, &mut errors);
+            for error in errors {
+                eprintln!("failed to format message {id}: {error}");
+            }
+            Some(text.into_owned())
+        })
+    }
+}
+
+fn bundle(id: &str, source: &str) -> FluentBundle<FluentResource> {
+    let resource = FluentResource::try_new(source.to_string())
+        .unwrap_or_else(|(_, errors)| panic!("invalid translations for {id}: {errors:?}"));
+
+    let mut bundle = FluentBundle::new_concurrent(vec![id.parse().unwrap()]);
+    // egui renders the bidirectional isolation marks instead of interpreting them
+    bundle.set_use_isolating(false);
+    bundle
+        .add_resource(resource)
+        .unwrap_or_else(|errors| panic!("duplicated translations for {id}: {errors:?}"));
+    bundle
+}
+
+/// Selects the language of the interface, `locale` (ex: `fr-FR`) overrides the system's one.
+///
+/// Has no effect once a message was translated.
+pub fn init(locale: Option<&str>) {
+    LOCALIZER.get_or_init(|| Localizer::new(locale));
+}
+
+fn localizer() -> &'static Localizer {
+    LOCALIZER.get_or_init(|| Localizer::new(None))
+}
+
+/// Translates the message `id`, see [`tr!`] for a shorter form.
+pub fn translate(id: &str, args: Option<&FluentArgs>) -> String {
+    localizer().format(id, args).unwrap_or_else(|| {
+        eprintln!("missing translation for {id}");
+        id.to_string()
+    })
+}
+
+/// Translated name of a region, falling back to the name of the region list.
+pub fn region_name(region: &Region) -> String {
+    let id = format!("regionname-{}", region.key.replace('/', "-"));
+    localizer()
+        .format(&id, None)
+        .unwrap_or_else(|| region.name.clone())
+}
+
+/// Translates a message, with optional `name = value` arguments.
+macro_rules! tr {
+    ($id:literal) => {
+        $crate::i18n::translate($id, None)
+    };
+    ($id:literal, $($name:ident = $value:expr),+ $(,)?) => {{
+        let mut args = fluent_bundle::FluentArgs::new();
+        $(args.set(stringify!($name), $value);)+
+        $crate::i18n::translate($id, Some(&args))
+    }};
+}
+pub(crate) use tr;
@@ -0,0 +1,258 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result, bail};
+use ipnetwork::IpNetwork;
+use iter_tools::Itertools;
+
+use crate::cidr;
+use crate::prefixes::{self, Region};
+use crate::regiondb::{self, RegionDb};
+
+mod asn;
+mod dropship;
+mod gcp;
+
+#[derive(clap::Subcommand)]
+pub enum ImportCommand {
+    /// convert dropship's Settings.h into a region database
+    Dropship {
+        /// path to the Settings.h header
+        header: PathBuf,
+
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+    /// replace the prefixes of Google Cloud regions with the ranges of cloud.json
+    Gcp {
+        /// Google Cloud's IP ranges (https://www.gstatic.com/ipranges/cloud.json)
+        file: PathBuf,
+
+        /// region to update as KEY=SCOPE, defaults to every `google/<scope>` region
+        #[arg(long = "region", value_parser = parse_scope)]
+        regions: Vec<(String, String)>,
+
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+    /// replace the prefixes of a region with the prefixes announced by an ASN
+    Asn {
+        /// RIPEstat announced-prefixes response, bgp.tools table export or one prefix per line
+        file: PathBuf,
+
+        /// key of the region to update
+        #[arg(long)]
+        region: String,
+
+        /// only keep prefixes announced by this ASN, when the dump records origins
+        #[arg(long)]
+        asn: Option<u32>,
+
+        /// only keep prefixes inside one of these networks
+        #[arg(long)]
+        within: Vec<IpNetwork>,
+
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+}
+
+#[derive(clap::Args)]
+pub struct OutputArgs {
+    /// where to write the region database
+    #[arg(long, short)]
+    output: PathBuf,
+
+    /// serial number of the database, defaults to one more than the cached database
+    #[arg(long)]
+    serial: Option<u64>,
+}
+
+pub fn import_main(command: ImportCommand) -> Result<()> {
+    let current = prefixes::published();
+
+    let (regions, output) = match command {
+        ImportCommand::Dropship { header, output } => {
+            let import = dropship::parse(&fs::read_to_string(header)?)?;
+            for warning in import.warnings {
+                eprintln!("warning: {warning}");
+            }
+
+            let mut regions = import.regions;
+
+            // IPv6 ping targets and continents are not part of the upstream metadata
+            for region in regions.iter_mut() {
+                if let Some(known) = current.iter().find(|r| r.key == region.key) {
+                    region.ping6 = known.ping6;
+                    region.continent = known.continent;
+                }
+            }
+
+            (regions, output)
+        }
+        ImportCommand::Gcp {
+            file,
+            regions: scopes,
+            output,
+        } => {
+            let ranges = gcp::CloudRanges::parse(&fs::read(file)?)?;
+            let scopes = if scopes.is_empty() {
+                current
+                    .iter()
+                    .filter_map(|r| {
+                        Some((r.key.clone(), r.key.strip_prefix("google/")?.to_string()))
+                    })
+                    .collect_vec()
+            } else {
+                scopes
+            };
+
+            let mut regions = current.clone();
+            for (key, scope) in scopes {
+                let prefixes = ranges.scope(&scope);
+                if prefixes.is_empty() {
+                    bail!("no ranges for scope {scope} in cloud.json");
+                }
+
+                find_region(&mut regions, &key)?.prefixes = prefixes;
+            }
+
+            (regions, output)
+        }
+        ImportCommand::Asn {
+            file,
+            region,
+            asn,
+            within,
+            output,
+        } => {
+            let announcements = asn::parse(&fs::read_to_string(file)?)?;
+            let prefixes = announcements
+                .into_iter()
+                .filter(|a| asn.is_none() || a.asn.is_none() || a.asn == asn)
+                .map(|a| a.prefix)
+                .filter(|prefix| {
+                    within.is_empty() || within.iter().any(|net| cidr::contains(net, prefix))
+                })
+                .unique()
+                .sorted_by_key(|prefix| (prefix.is_ipv6(), prefix.network(), prefix.prefix()))
+                .collect_vec();
+            if prefixes.is_empty() {
+                bail!("no prefixes left after filtering");
+            }
+
+            let mut regions = current.clone();
+            find_region(&mut regions, &region)?.prefixes = prefixes;
+
+            (regions, output)
+        }
+    };
+
+    print_diff(&current, &regions);
+    write_db(regions, output)
+}
+
+fn parse_scope(value: &str) -> Result<(String, String), String> {
+    value
+        .split_once('=')
+        .map(|(key, scope)| (key.to_string(), scope.to_string()))
+        .ok_or_else(|| format!("expected KEY=SCOPE, got {value}"))
+}
+
+fn find_region<'a>(regions: &'a mut [Region], key: &str) -> Result<&'a mut Region> {
+    regions
+        .iter_mut()
+        .find(|r| r.key == key)
+        .with_context(|| format!("no region {key}, add it to the region database first"))
+}
+
+fn write_db(regions: Vec<Region>, args: OutputArgs) -> Result<()> {
+    let serial = match args.serial {
+        Some(serial) => serial,
+        None => regiondb::load_cached()
+            .ok()
+            .flatten()
+            .map_or(0, |db| db.serial)
+            .saturating_add(1),
+    };
+
+    let regions = regions
+        .into_iter()
+        .sorted_by(|a, b| a.key.cmp(&b.key))
+        .collect_vec();
+    fs::write(
+        &args.output,
+        serde_json::to_vec_pretty(&RegionDb { serial, regions })?,
+    )?;
+    eprintln!(
+        "wrote {} with serial {serial}, sign it before publishing",
+        args.output.display()
+    );
+
+    Ok(())
+}
+
+/// Prints added and removed regions and CIDRs between two region sets.
+fn print_diff(current: &[Region], imported: &[Region]) {
+    let current = current
+        .iter()
+        .map(|r| (&r.key, r))
+        .collect::<HashMap<_, _>>();
+    let imported = imported
+        .iter()
+        .map(|r| (&r.key, r))
+        .collect::<HashMap<_, _>>();
+
+    let keys = current
+        .keys()
+        .chain(imported.keys())
+        .copied()
+        .collect::<BTreeSet<_>>();
+
+    let mut changes = 0;
+    for key in keys {
+        match (current.get(key), imported.get(key)) {
+            (None, Some(region)) => {
+                println!(
+                    "+ {key} ({}, {} prefixes)",
+                    region.name,
+                    region.prefixes.len()
+                );
+                changes += 1;
+            }
+            (Some(region), None) => {
+                println!(
+                    "- {key} ({}, {} prefixes)",
+                    region.name,
+                    region.prefixes.len()
+                );
+                changes += 1;
+            }
+            (Some(old), Some(new)) => {
+                let old_prefixes = old.prefixes.iter().collect::<BTreeSet<_>>();
+                let new_prefixes = new.prefixes.iter().collect::<BTreeSet<_>>();
+                let added = new_prefixes.difference(&old_prefixes).collect_vec();
+                let removed = old_prefixes.difference(&new_prefixes).collect_vec();
+
+                if added.is_empty() && removed.is_empty() {
+                    continue;
+                }
+
+                println!("~ {key}");
+                for prefix in added {
+                    println!("    + {prefix}");
+                }
+                for prefix in removed {
+                    println!("    - {prefix}");
+                }
+                changes += 1;
+            }
+            (None, None) => unreachable!(),
+        }
+    }
+
+    if changes == 0 {
+        println!("no prefix changes");
+    }
+}
@@ -0,0 +1,139 @@
+//! Parser for the prefixes announced by an autonomous system.
+//!
+//! Accepts RIPEstat's announced-prefixes response
+//! (https://stat.ripe.net/data/announced-prefixes/data.json?resource=AS3786),
+//! bgp.tools' `table.jsonl` and `table.txt` exports, or one prefix per line.
+
+use anyhow::{Context, Result};
+use ipnetwork::IpNetwork;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+struct RipeStat {
+    data: RipeStatData,
+}
+
+#[derive(Deserialize)]
+struct RipeStatData {
+    prefixes: Vec<RipeStatPrefix>,
+}
+
+#[derive(Deserialize)]
+struct RipeStatPrefix {
+    prefix: IpNetwork,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+struct BgpToolsEntry {
+    cidr: IpNetwork,
+    asn: u32,
+}
+
+/// A prefix along with its origin, when the dump records it.
+pub struct Announcement {
+    pub prefix: IpNetwork,
+    pub asn: Option<u32>,
+}
+
+pub fn parse(source: &str) -> Result<Vec<Announcement>> {
+    if let Ok(ripestat) = serde_json::from_str::<RipeStat>(source) {
+        return Ok(ripestat
+            .data
+            .prefixes
+            .into_iter()
+            .map(|p| Announcement {
+                prefix: p.prefix,
+                asn: None,
+            })
+            .collect());
+    }
+
+    let mut announcements = Vec::new();
+
+    for (n, line) in source.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let announcement = if line.starts_with('{') {
+            let entry: BgpToolsEntry = serde_json::from_str(line)
+                .with_context(|| format!("malformed entry on line {}", n + 1))?;
+            Announcement {
+                prefix: entry.cidr,
+                asn: Some(entry.asn),
+            }
+        } else {
+            let mut fields = line.split_whitespace();
+            let prefix = fields.next().unwrap();
+            Announcement {
+                prefix: prefix
+                    .parse()
+                    .with_context(|| format!("invalid prefix {prefix} on line {}", n + 1))?,
+                asn: fields
+                    .next()
+                    .map(|asn| asn.trim_start_matches("AS").parse())
+                    .transpose()
+                    .with_context(|| format!("invalid ASN on line {}", n + 1))?,
+            }
+        };
+        announcements.push(announcement);
+    }
+
+    Ok(announcements)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn announcements(source: &str) -> Vec<(String, Option<u32>)> {
+        parse(source)
+            .unwrap()
+            .into_iter()
+            .map(|a| (a.prefix.to_string(), a.asn))
+            .collect()
+    }
+
+    #[test]
+    fn parses_ripestat() {
+        assert_eq!(
+            announcements(include_str!("fixtures/ripestat.json")),
+            [("24.105.0.0/18".into(), None), ("2620:f::/48".into(), None)]
+        );
+    }
+
+    #[test]
+    fn parses_bgp_tools_jsonl() {
+        assert_eq!(
+            announcements(include_str!("fixtures/table.jsonl")),
+            [
+                ("24.105.0.0/18".into(), Some(57976)),
+                ("64.224.0.0/21".into(), Some(57976)),
+                ("2620:f::/48".into(), Some(57976)),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_plain_text() {
+        assert_eq!(
+            announcements(include_str!("fixtures/table.txt")),
+            [
+                ("24.105.0.0/18".into(), Some(57976)),
+                ("64.224.0.0/21".into(), Some(57976)),
+                ("137.221.64.0/18".into(), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_malformed_lines() {
+        let error = |source| format!("{:#}", parse(source).err().unwrap());
+
+        assert!(error("10.0.0.0/8\n10.0.0.0/33\n").contains("line 2"));
+        assert!(error("10.0.0.0/8 ASX\n").contains("invalid ASN on line 1"));
+        assert!(error("{\"CIDR\":\"10.0.0.0/8\"}\n").contains("malformed entry on line 1"));
+    }
+}
@@ -0,0 +1,370 @@
+//! Parser for the region tables of dropship's `Settings.h`.
+//!
+//! See https://github.com/stowmyy/dropship/blob/main/dropship/dropship/src/core/Settings.h
+
+use std::collections::HashMap;
+
+use anyhow::{Context, Result, anyhow, bail};
+use indexmap::IndexMap;
+use iter_tools::Itertools;
+
+use crate::prefixes::Region;
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Str(String),
+    Ident(String),
+    Punct(char),
+}
+
+#[derive(Debug)]
+enum Value {
+    /// One or more adjacent string literals.
+    Str(String),
+    /// A reference to a constant.
+    Ident(String),
+    List(Vec<Value>),
+}
+
+/// Metadata of a region as described by the `.description/.ip_ping` entries.
+#[derive(Clone)]
+struct Meta {
+    name: String,
+    code: String,
+    ping: String,
+}
+
+/// Regions found in the header, along with warnings about incomplete entries.
+pub struct Import {
+    pub regions: Vec<Region>,
+    pub warnings: Vec<String>,
+}
+
+pub fn parse(source: &str) -> Result<Import> {
+    let tokens = tokenize(source)?;
+
+    let mut constants = HashMap::new();
+    let mut blocks = Vec::new();
+    let mut metas = Vec::new();
+
+    let mut pos = 0;
+    while pos < tokens.len() {
+        if let Some((name, value, next)) = parse_constant(&tokens, pos) {
+            constants.insert(name, value);
+            pos = next;
+        } else if let Some((key, mut fields, next)) = parse_entry(&tokens, pos) {
+            if let Some(block) = fields.shift_remove("block") {
+                blocks.push((key, block));
+            } else if fields.contains_key("ip_ping") {
+                metas.push((key, fields));
+            }
+            pos = next;
+        } else {
+            pos += 1;
+        }
+    }
+
+    let resolve = |value: &Value| -> Result<String> {
+        match value {
+            Value::Str(s) => Ok(s.clone()),
+            Value::Ident(name) => constants
+                .get(name)
+                .cloned()
+                .ok_or_else(|| anyhow!("unknown constant {name}")),
+            Value::List(_) => bail!("expected a string"),
+        }
+    };
+
+    let mut meta_by_key = HashMap::new();
+    for (name, fields) in &metas {
+        let meta = Meta {
+            name: name.clone(),
+            code: resolve(fields.get("description").context("missing .description")?)?,
+            ping: resolve(&fields["ip_ping"])?,
+        };
+
+        // the region keys are listed in the last list field
+        let keys = fields
+            .values()
+            .filter_map(|value| match value {
+                Value::List(keys) => Some(keys),
+                _ => None,
+            })
+            .next_back()
+            .with_context(|| format!("no region keys listed for {name}"))?;
+
+        for key in keys {
+            meta_by_key.insert(resolve(key)?, meta.clone());
+        }
+    }
+
+    let mut regions = Vec::new();
+    let mut warnings = Vec::new();
+
+    for (key, block) in blocks {
+        let Some(meta) = meta_by_key.remove(&key) else {
+            warnings.push(format!("{key} has no metadata, skipping it"));
+            continue;
+        };
+
+        let block = resolve(&block).with_context(|| format!("invalid .block of {key}"))?;
+        let prefixes = block
+            .split(',')
+            .map(str::trim)
+            .filter(|cidr| !cidr.is_empty())
+            .map(|cidr| {
+                cidr.parse()
+                    .with_context(|| format!("invalid prefix {cidr} in {key}"))
+            })
+            .try_collect()?;
+
+        regions.push(Region {
+            key,
+            name: meta.name,
+            code: meta.code,
+            ping: meta
+                .ping
+                .parse()
+                .with_context(|| format!("invalid ping address {}", meta.ping))?,
+            ping6: None,
+            continent: Default::default(),
+            prefixes,
+        });
+    }
+
+    for key in meta_by_key.keys().sorted() {
+        warnings.push(format!("{key} has metadata but no prefixes, skipping it"));
+    }
+
+    Ok(Import { regions, warnings })
+}
+
+/// Matches `static const std::string NAME { "..." };` or `... NAME = "...";`.
+fn parse_constant(tokens: &[Token], pos: usize) -> Option<(String, String, usize)> {
+    let [
+        Token::Ident(kw_static),
+        Token::Ident(kw_const),
+        Token::Ident(ty),
+        Token::Ident(name),
+        open,
+        ..,
+    ] = tokens.get(pos..)?
+    else {
+        return None;
+    };
+
+    if kw_static != "static" || kw_const != "const" || ty != "std::string" {
+        return None;
+    }
+
+    let mut pos = pos + 5;
+    let (value, next) = parse_strings(tokens, pos)?;
+    pos = next;
+
+    if *open == Token::Punct('{') {
+        expect(tokens, pos, '}')?;
+        pos += 1;
+    } else if *open != Token::Punct('=') {
+        return None;
+    }
+
+    Some((name.clone(), value, pos))
+}
+
+/// Matches `{ "key", { .field = value, ... } }`.
+fn parse_entry(tokens: &[Token], pos: usize) -> Option<(String, IndexMap<String, Value>, usize)> {
+    expect(tokens, pos, '{')?;
+    let Some(Token::Str(key)) = tokens.get(pos + 1) else {
+        return None;
+    };
+    expect(tokens, pos + 2, ',')?;
+    expect(tokens, pos + 3, '{')?;
+
+    let mut pos = pos + 4;
+    let mut fields = IndexMap::new();
+
+    loop {
+        match tokens.get(pos)? {
+            Token::Punct('}') => break,
+            Token::Punct(',') => pos += 1,
+            Token::Punct('.') => {
+                let Token::Ident(field) = tokens.get(pos + 1)? else {
+                    return None;
+                };
+                expect(tokens, pos + 2, '=')?;
+                let (value, next) = parse_value(tokens, pos + 3)?;
+                fields.insert(field.clone(), value);
+                pos = next;
+            }
+            _ => return None,
+        }
+    }
+
+    expect(tokens, pos + 1, '}')?;
+
+    Some((key.clone(), fields, pos + 2))
+}
+
+fn parse_value(tokens: &[Token], pos: usize) -> Option<(Value, usize)> {
+    match tokens.get(pos)? {
+        Token::Str(_) => parse_strings(tokens, pos).map(|(s, next)| (Value::Str(s), next)),
+        Token::Ident(name) => Some((Value::Ident(name.clone()), pos + 1)),
+        Token::Punct('{') => {
+            let mut pos = pos + 1;
+            let mut values = Vec::new();
+
+            loop {
+                match tokens.get(pos)? {
+                    Token::Punct('}') => return Some((Value::List(values), pos + 1)),
+                    Token::Punct(',') => pos += 1,
+                    _ => {
+                        let (value, next) = parse_value(tokens, pos)?;
+                        values.push(value);
+                        pos = next;
+                    }
+                }
+            }
+        }
+        Token::Punct(_) => None,
+    }
+}
+
+/// Concatenates adjacent string literals.
+fn parse_strings(tokens: &[Token], mut pos: usize) -> Option<(String, usize)> {
+    let mut value = String::new();
+    let start = pos;
+
+    while let Some(Token::Str(s)) = tokens.get(pos) {
+        value.push_str(s);
+        pos += 1;
+    }
+
+    (pos > start).then_some((value, pos))
+}
+
+fn expect(tokens: &[Token], pos: usize, punct: char) -> Option<()> {
+    (tokens.get(pos)? == &Token::Punct(punct)).then_some(())
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = source.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            '/' if chars.peek() == Some(&'/') => {
+                chars.by_ref().take_while(|&c| c != '\n').for_each(drop);
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut prev = '\0';
+                for c in chars.by_ref() {
+                    if prev == '*' && c == '/' {
+                        break;
+                    }
+                    prev = c;
+                }
+            }
+            '#' => {
+                // preprocessor directives span the rest of the line
+                chars.by_ref().take_while(|&c| c != '\n').for_each(drop);
+            }
+            '"' => {
+                let mut s = String::new();
+                loop {
+                    match chars.next().context("unterminated string literal")? {
+                        '"' => break,
+                        '\\' => s.push(chars.next().context("unterminated string literal")?),
+                        c => s.push(c),
+                    }
+                }
+                tokens.push(Token::Str(s));
+            }
+            c if c.is_alphanumeric() || c == '_' => {
+                let mut ident = c.to_string();
+                while let Some(&c) = chars.peek() {
+                    if c.is_alphanumeric() || c == '_' || c == ':' {
+                        ident.push(c);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                tokens.push(Token::Ident(ident));
+            }
+            c => tokens.push(Token::Punct(c)),
+        }
+    }
+
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_settings() {
+        let import = parse(include_str!("fixtures/Settings.h")).unwrap();
+
+        let regions = import
+            .regions
+            .iter()
+            .map(|r| {
+                let prefixes = r.prefixes.iter().join(",");
+                (
+                    r.key.as_str(),
+                    r.name.as_str(),
+                    r.code.as_str(),
+                    r.ping.to_string(),
+                    prefixes,
+                )
+            })
+            .collect_vec();
+
+        assert_eq!(
+            regions,
+            [
+                (
+                    "google/europe-west4",
+                    "Netherlands",
+                    "GNL1",
+                    "34.90.0.1".into(),
+                    "34.90.0.0/15,35.204.0.0/16".into()
+                ),
+                (
+                    "blizzard/ord1",
+                    "Chicago",
+                    "ORD1",
+                    "64.224.0.1".into(),
+                    "64.224.0.0/21,24.105.40.0/21".into()
+                ),
+                (
+                    "blizzard/las1",
+                    "Los Angeles",
+                    "LAS1",
+                    "64.224.24.1".into(),
+                    "64.224.24.0/23".into()
+                ),
+            ]
+        );
+        assert_eq!(
+            import.warnings,
+            [
+                "test/unlisted has no metadata, skipping it",
+                "blizzard/cdg1 has metadata but no prefixes, skipping it",
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_constant() {
+        let source = r#"
+            { "a", { .block = MISSING } },
+            { "A", { .description = "A1", .ip_ping = "192.0.2.1", .blocks = { "a" } } },
+        "#;
+
+        assert!(parse(source).is_err());
+    }
+}
@@ -0,0 +1,25 @@
+#pragma once
+
+#include <map>
+#include <string>
+
+/* trimmed from dropship's Settings.h */
+
+static const std::string GCP_EUROPE_WEST4 { "34.90.0.0/15,"
+    "35.204.0.0/16" };
+static const std::string BLIZZARD_LAS1 = "64.224.24.0/23";
+
+static const std::map<std::string, Endpoint> endpoints {
+    // { "test", { .block = "" }},
+    { "google/europe-west4", { .block = GCP_EUROPE_WEST4 } },
+    { "blizzard/ord1", { .block = "64.224.0.0/21," "24.105.40.0/21" } },
+    { "blizzard/las1", { .block = BLIZZARD_LAS1 } },
+    { "test/unlisted", { .block = "198.51.100.0/24" } },
+};
+
+static const std::vector<Server> servers {
+    { "Netherlands", { .description = "GNL1", .ip_ping = "34.90.0.1", .blocks = { "google/europe-west4" } } },
+    { "Chicago", { .description = "ORD1", .ip_ping = "64.224.0.1", .blocks = { "blizzard/ord1" } } },
+    { "Los Angeles", { .description = "LAS1", .ip_ping = "64.224.24.1", .blocks = { "blizzard/las1" } } },
+    { "Paris", { .description = "CDG1", .ip_ping = "192.0.2.1", .blocks = { "blizzard/cdg1" } } },
+};
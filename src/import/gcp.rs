@@ -0,0 +1,75 @@
+//! Parser for Google Cloud's published IP ranges.
+//!
+//! See https://www.gstatic.com/ipranges/cloud.json
+
+use anyhow::{Context, Result};
+use ipnetwork::IpNetwork;
+use iter_tools::Itertools;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+struct Ranges {
+    prefixes: Vec<Range>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Range {
+    ipv4_prefix: Option<IpNetwork>,
+    ipv6_prefix: Option<IpNetwork>,
+    scope: String,
+}
+
+/// Ranges of every scope (ex: `europe-north1`) in the file.
+pub struct CloudRanges(Vec<Range>);
+
+impl CloudRanges {
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        let ranges: Ranges = serde_json::from_slice(data).context("malformed cloud.json")?;
+        Ok(Self(ranges.prefixes))
+    }
+
+    /// Prefixes of a scope, IPv4 first.
+    pub fn scope(&self, scope: &str) -> Vec<IpNetwork> {
+        let ranges = self.0.iter().filter(|range| range.scope == scope);
+
+        ranges
+            .clone()
+            .filter_map(|range| range.ipv4_prefix)
+            .chain(ranges.filter_map(|range| range.ipv6_prefix))
+            .collect_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scope(ranges: &CloudRanges, scope: &str) -> Vec<String> {
+        ranges
+            .scope(scope)
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn filters_by_scope() {
+        let ranges = CloudRanges::parse(include_bytes!("fixtures/cloud.json")).unwrap();
+
+        assert_eq!(
+            scope(&ranges, "europe-north1"),
+            ["34.88.0.0/16", "35.217.0.0/18", "2600:1900:4150::/44"]
+        );
+        assert_eq!(
+            scope(&ranges, "europe-west4"),
+            ["34.90.0.0/15", "2600:1900:4060::/44"]
+        );
+        assert!(scope(&ranges, "europe-west1").is_empty());
+    }
+
+    #[test]
+    fn rejects_malformed_file() {
+        assert!(CloudRanges::parse(br#"{"prefixes": [{"ipv4Prefix": "34.88.0.0/33"}]}"#).is_err());
+    }
+}
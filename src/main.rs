@@ -1,49 +1,110 @@
-use std::ops::Not;
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::time::Duration;
 
 #[cfg(target_os = "linux")]
-use ::{anyhow::ensure, clap::Parser, libc::geteuid};
+use ::{
+    anyhow::ensure,
+    libc::geteuid,
+    raw_window_handle::{HasDisplayHandle, RawDisplayHandle},
+};
 use anyhow::{Result, anyhow};
+use clap::Parser;
+use eframe::egui::collapsing_header::CollapsingState;
 use eframe::egui::{
-    Align, CentralPanel, ImageButton, Layout, ScrollArea, TopBottomPanel, ViewportBuilder, Widget,
-    global_theme_preference_switch, include_image, vec2,
+    Align, CentralPanel, Checkbox, ComboBox, DragValue, Grid, Key, Layout, Modifiers, RichText,
+    ScrollArea, TextEdit, TopBottomPanel, ViewportBuilder, Window, global_theme_preference_switch,
+    include_image, vec2,
 };
 use eframe::{NativeOptions, egui};
 use indexmap::IndexMap;
+use ipnetwork::IpNetwork;
 use iter_tools::Itertools;
 use rfd::AsyncFileDialog;
 use tokio::sync::watch;
 use tokio::task::JoinHandle;
 
+use crate::autoselect::{AutoSelect, AutoSelectMode};
+use crate::blocking::BlockMode;
+use crate::cli::{Cli, Command};
+use crate::config::{Config, Profile};
+use crate::custom::CustomEditor;
 use crate::daemon::KillError;
-use crate::modal::{ModalDisplay, ModalLevel};
-use crate::ping::PingReceiver;
-use crate::regions::{RegionEntry, RegionSortBy, RegionSorting};
-
+use crate::i18n::tr;
+use crate::modal::{ModalDisplay, ModalLevel, ModalQueue, ModalSender};
+#[cfg(target_os = "linux")]
+use crate::monitor::DaemonMonitor;
+#[cfg(target_os = "linux")]
+use crate::observer::DaemonActivity;
+use crate::ping::{PingControl, PingReceiver};
+use crate::prefixes::Continent;
+use crate::profiles::{ProfileAction, ProfileManager};
+use crate::recorder::{RecordFormat, Recorder};
+use crate::regions::{
+    AppliedSelection, BulkSelection, RegionEntry, RegionFilter, RegionGrouping, RegionSortBy,
+    RegionSorting,
+};
+use crate::traceroute::Traceroute;
+#[cfg(target_os = "linux")]
+use crate::tray::{TrayCommand, TrayIcon, TrayState};
+
+mod autoselect;
+mod blocking;
+mod cidr;
+mod cli;
+mod config;
+mod custom;
 mod daemon;
 mod fw;
+mod i18n;
+mod import;
+mod measure;
 mod modal;
+#[cfg(target_os = "linux")]
+mod monitor;
+#[cfg(target_os = "linux")]
+mod observer;
 mod ping;
 mod prefixes;
+mod profiles;
+mod recorder;
+mod regiondb;
 mod regions;
+mod traceroute;
+#[cfg(target_os = "linux")]
+mod tray;
 mod util;
+mod validate;
 mod widgets;
 
 fn main() -> Result<()> {
+    let cli = Cli::parse();
+
     #[cfg(target_os = "linux")]
-    if let Ok(cli) = daemon::Cli::try_parse() {
-        if cli.kill {
+    {
+        if cli.daemon.kill {
             daemon::kill().ok();
             return Ok(());
         }
 
-        if cli.daemon {
+        if cli.daemon.daemon {
             ensure!(unsafe { geteuid() == 0 }, "daemon not running as root");
 
-            daemon::daemon_main(cli)?;
+            daemon::daemon_main(cli.daemon)?;
             return Ok(());
         }
     }
 
+    if let Some(command) = cli.command {
+        return match command {
+            Command::Measure(args) => measure::measure_main(args),
+            Command::Db(command) => regiondb::db_main(command),
+            Command::Import(command) => import::import_main(command),
+            Command::Validate(args) => validate::validate_main(args),
+            Command::Apply(args) => profiles::apply_main(args),
+        };
+    }
+
     let opts = NativeOptions {
         viewport: ViewportBuilder::default()
             .with_inner_size(vec2(300., 400.))
@@ -53,14 +114,28 @@ fn main() -> Result<()> {
     eframe::run_native(
         "ow2 server picker",
         opts,
-        Box::new(|cc| Ok(Box::new(App::new(cc).unwrap()))),
+        Box::new(|cc| Ok(Box::new(App::new(cc, cli.record).unwrap()))),
     )
     .map_err(|e| anyhow!("{}", e.to_string()))
 }
 
+enum FileSelectionKind {
+    /// Game executable, optionally starting the daemon after receiving the file.
+    GameExe { start_daemon: bool },
+
+    /// Destination file of the ping recording.
+    Recording,
+}
+
+/// Delay used to batch repaints caused by ping updates.
+const PING_REPAINT_DELAY: Duration = Duration::from_millis(250);
+
+/// Amount of region list issues shown when the application starts.
+const MAX_REGION_WARNINGS: usize = 10;
+
 struct FileSelectionTask {
-    /// Whether to start the daemon after receiving the file.
-    start_daemon: bool,
+    /// What the selected file is going to be used for.
+    kind: FileSelectionKind,
 
     /// An associated join handle.
     handle: JoinHandle<Option<rfd::FileHandle>>,
@@ -84,21 +159,74 @@ struct App {
     /// A sender for the current file selection task.
     file_selection_task_tx: watch::Sender<Option<FileSelectionTask>>,
 
-    /// A receiver for the current modal display.
-    modal_rx: watch::Receiver<Option<ModalDisplay>>,
+    /// Messages waiting to be shown, along with the log of the shown ones.
+    modals: ModalQueue,
 
-    /// A sender for the current modal display.
-    modal_tx: watch::Sender<Option<ModalDisplay>>,
+    /// A sender of messages to show.
+    modal_tx: ModalSender,
 
     /// A receiver of the ping updates.
     ping_rx: Option<PingReceiver>,
 
+    /// Pause control of the pinger.
+    ping_control: Option<PingControl>,
+
+    /// Active recording of the ping updates.
+    recorder: Option<Recorder>,
+
+    /// Traceroute shown in its own window.
+    traceroute: Option<Traceroute>,
+
+    /// Latency based region selection.
+    auto_select: AutoSelect,
+
     /// Sorting configuration.
     sort: RegionSorting,
+
+    /// How regions are grouped in the list.
+    grouping: RegionGrouping,
+
+    /// Which regions are shown in the list.
+    filter: RegionFilter,
+
+    /// Selection blocking was enabled with, `None` while blocking is disabled.
+    applied: Option<AppliedSelection>,
+
+    /// Editor of the custom regions and prefix overrides.
+    custom_editor: CustomEditor,
+
+    /// Management of the saved selections.
+    profiles: ProfileManager,
+
+    /// Configuration the application was started with, updated when saving settings.
+    config: Config,
+
+    /// Dropped traffic and connections of the game observed by the daemon.
+    #[cfg(target_os = "linux")]
+    activity: DaemonActivity,
+
+    /// Notifications about the daemon started by the application.
+    #[cfg(target_os = "linux")]
+    monitor: Option<DaemonMonitor>,
+
+    /// Status notifier icon, when a host is available to show it.
+    #[cfg(target_os = "linux")]
+    tray: Option<TrayIcon>,
+
+    /// Whether the window was hidden to the tray.
+    hidden: bool,
+
+    /// Whether the window runs on Wayland, where it can only be minimized instead of hidden.
+    #[cfg(target_os = "linux")]
+    wayland: bool,
+
+    /// Whether closing the window should exit instead of hiding it.
+    #[cfg(target_os = "linux")]
+    quitting: bool,
 }
 
 impl App {
-    fn new(cc: &eframe::CreationContext<'_>) -> Result<Self> {
+    fn new(cc: &eframe::CreationContext<'_>, record: Option<PathBuf>) -> Result<Self> {
         egui_extras::install_image_loaders(&cc.egui_ctx);
 
         cc.egui_ctx.style_mut(|style| {
@@ -110,15 +238,24 @@ impl App {
             .enable_time()
             .build()?;
 
-        let region_states = prefixes::load()
+        let config = Config::load()
+            .inspect_err(|e| eprintln!("failed to load the configuration: {e:#}"))
+            .unwrap_or_default();
+
+        i18n::init(config.locale.as_deref());
+
+        let (regions, region_warnings) = prefixes::load_with_warnings();
+        let region_states = regions
             .into_iter()
             .sorted_by_key(|region| region.name.clone())
             .map(|region| {
                 (
                     region.key.clone(),
                     RegionEntry {
+                        ping6: region.ping6.map(|_| ping::PingStatus::Unknown),
                         region,
                         ping: ping::PingStatus::Unknown,
+                        stats: Default::default(),
                         selected: false,
                     },
                 )
@@ -128,30 +265,61 @@ impl App {
         let (file_selection_task_tx, file_selection_task_rx) =
             watch::channel(Option::<FileSelectionTask>::None);
 
-        let (modal_tx, modal_rx) = watch::channel(Option::<ModalDisplay>::None);
+        let (modal_tx, modals) = modal::channel(&cc.egui_ctx);
+
+        if !region_warnings.is_empty() {
+            let mut content = region_warnings.iter().take(MAX_REGION_WARNINGS).join("\n");
+            if region_warnings.len() > MAX_REGION_WARNINGS {
+                content.push_str(&format!("\n{}", tr!("region-issues-more")));
+            }
+
+            modal_tx.send(ModalDisplay {
+                level: ModalLevel::Warning,
+                title: tr!("region-issues-title"),
+                content,
+            });
+        }
 
-        let ping_rx = ping::setup_pinger(
+        if let Some(url) = config.region_db_url.clone() {
+            let modal_tx = modal_tx.clone();
+
+            runtime.spawn_blocking(move || match regiondb::update(&url) {
+                Ok(Some(_)) => modal_tx.send(ModalDisplay {
+                    level: ModalLevel::Info,
+                    title: tr!("region-db-updated-title"),
+                    content: tr!("restart-for-regions"),
+                }),
+                Ok(None) => {}
+                Err(e) => eprintln!("failed to update the region database: {e:#}"),
+            });
+        }
+
+        let (ping_rx, ping_control) = ping::setup_pinger(
             &runtime,
             region_states
                 .iter()
-                .map(|(key, entry)| (key.clone(), entry.region.ping))
+                .flat_map(|(key, entry)| {
+                    entry
+                        .region
+                        .ping_targets()
+                        .map(|target| (key.clone(), target))
+                })
                 .sorted()
                 .collect_vec(),
+            ping::DEFAULT_INTERVAL,
         )
         .inspect_err(|_| {
-            modal_tx
-                .send(Some(ModalDisplay {
-                    level: ModalLevel::Warning,
-                    title: "Pings are unavailable".to_string(),
-                    content: "Your system may not support this feature".to_string(),
-                }))
-                .expect("failed to send an error modal");
+            modal_tx.send(ModalDisplay {
+                level: ModalLevel::Warning,
+                title: tr!("pings-unavailable-title"),
+                content: tr!("pings-unavailable-content"),
+            });
         })
-        .ok();
+        .ok()
+        .unzip();
 
         runtime.spawn({
             let mut fst_rx = file_selection_task_rx.clone();
-            let mut m_rx = modal_rx.clone();
             let mut p_sub = ping_rx.as_ref().map(|rx| rx.resubscribe());
 
             let ctx = cc.egui_ctx.clone();
@@ -160,9 +328,12 @@ impl App {
                 loop {
                     tokio::select! {
                         result = fst_rx.changed() => if result.is_err() { break },
-                        result = m_rx.changed() => if result.is_err() { break },
                         result = p_sub.as_mut().unwrap().recv(), if p_sub.is_some() => {
                             if result.is_err() { break }
+
+                            // updates of a ping round arrive in bursts, coalesce their repaints
+                            ctx.request_repaint_after(PING_REPAINT_DELAY);
+                            continue;
                         },
                     }
 
@@ -171,23 +342,61 @@ impl App {
             }
         });
 
-        Ok(Self {
+        let mut app = Self {
             runtime,
             file_selection_task_rx,
             file_selection_task_tx,
-            modal_rx,
+            modals,
             modal_tx,
             game_exe: None,
             region_states,
             ping_rx,
-            sort: Default::default(),
-        })
+            ping_control,
+            recorder: None,
+            traceroute: None,
+            auto_select: Default::default(),
+            sort: config.sort.clone(),
+            grouping: RegionGrouping::None,
+            filter: Default::default(),
+            applied: None,
+            custom_editor: Default::default(),
+            profiles: Default::default(),
+            config,
+            #[cfg(target_os = "linux")]
+            activity: Default::default(),
+            #[cfg(target_os = "linux")]
+            monitor: None,
+            #[cfg(target_os = "linux")]
+            tray: None,
+            hidden: false,
+            #[cfg(target_os = "linux")]
+            wayland: cc
+                .display_handle()
+                .is_ok_and(|handle| matches!(handle.as_raw(), RawDisplayHandle::Wayland(_))),
+            #[cfg(target_os = "linux")]
+            quitting: false,
+        };
+
+        #[cfg(target_os = "linux")]
+        {
+            app.tray = TrayIcon::spawn(&app.runtime, cc.egui_ctx.clone())
+                .inspect_err(|e| eprintln!("failed to show the tray icon: {e:#}"))
+                .ok();
+        }
+
+        app.apply_sort();
+
+        if let Some(path) = record {
+            app.start_recording(path);
+        }
+
+        Ok(app)
     }
 
     fn run_exe_selection(&self, start_daemon: bool) {
         let handle = self.runtime.spawn(async move {
             AsyncFileDialog::new()
-                .set_title("Find your Overwatch installation")
+                .set_title(tr!("game-dialog-title"))
                 .add_filter("Overwatch.exe", &["exe"])
                 .pick_file()
                 .await
@@ -195,7 +404,26 @@ impl App {
 
         self.file_selection_task_tx
             .send(Some(FileSelectionTask {
-                start_daemon,
+                kind: FileSelectionKind::GameExe { start_daemon },
+                handle,
+            }))
+            .expect("failed to send file selection task");
+    }
+
+    fn run_recording_selection(&self) {
+        let handle = self.runtime.spawn(async move {
+            AsyncFileDialog::new()
+                .set_title(tr!("record-dialog-title"))
+                .set_file_name("pings.csv")
+                .add_filter("CSV", &["csv"])
+                .add_filter("JSON lines", &["jsonl"])
+                .save_file()
+                .await
+        });
+
+        self.file_selection_task_tx
+            .send(Some(FileSelectionTask {
+                kind: FileSelectionKind::Recording,
                 handle,
             }))
             .expect("failed to send file selection task");
@@ -212,59 +440,204 @@ impl App {
             let task = self.file_selection_task_tx.send_replace(None).unwrap();
             let file = self.runtime.block_on(task.handle);
             if let Err(e) = file {
-                self.modal_tx
-                    .send(Some(ModalDisplay {
-                        level: ModalLevel::Error,
-                        title: "Unable to read the file selection".to_string(),
-                        content: e.to_string(),
-                    }))
-                    .expect("failed to send modal");
+                self.modal_tx.send(ModalDisplay {
+                    level: ModalLevel::Error,
+                    title: tr!("file-selection-failed-title"),
+                    content: e.to_string(),
+                });
             } else if let Some(file) = file.unwrap() {
-                self.game_exe = Some(file);
+                match task.kind {
+                    FileSelectionKind::GameExe { start_daemon } => {
+                        self.game_exe = Some(file);
 
-                if task.start_daemon {
-                    self.start_daemon();
+                        if start_daemon {
+                            self.start_daemon();
+                        }
+                    }
+                    FileSelectionKind::Recording => self.start_recording(file.path().into()),
                 }
             }
         }
     }
 
+    fn start_recording(&mut self, path: PathBuf) {
+        let Some(rx) = self.ping_rx.as_ref() else {
+            self.modal_tx.send(ModalDisplay {
+                level: ModalLevel::Error,
+                title: tr!("record-failed-title"),
+                content: tr!("record-unavailable"),
+            });
+            return;
+        };
+
+        let format = RecordFormat::from_path(&path);
+        match Recorder::start(&self.runtime, rx.resubscribe(), path, format) {
+            Ok(recorder) => self.recorder = Some(recorder),
+            Err(e) => self.modal_tx.send(ModalDisplay {
+                level: ModalLevel::Error,
+                title: tr!("record-failed-title"),
+                content: tr!("record-open-failed", error = e.to_string()),
+            }),
+        }
+    }
+
+    fn handle_recorder(&mut self) {
+        let result = self
+            .recorder
+            .as_mut()
+            .and_then(|recorder| recorder.poll(&self.runtime));
+
+        if let Some(result) = result {
+            self.recorder = None;
+
+            if let Err(e) = result {
+                self.modal_tx.send(ModalDisplay {
+                    level: ModalLevel::Error,
+                    title: tr!("record-stopped-title"),
+                    content: tr!("record-write-failed", error = e.to_string()),
+                });
+            }
+        }
+    }
+
     fn handle_ping_updates(&mut self) {
         let mut had_updates = false;
 
         if let Some(rx) = self.ping_rx.as_mut() {
-            while let Ok(ping::PingUpdate(key, status)) = rx.try_recv() {
+            while let Ok(ping::PingUpdate(key, target, status)) = rx.try_recv() {
                 had_updates = true;
 
                 self.region_states.get_mut(&key).map_or_else(
                     || panic!("failed to retrieve region {key} for ping update"),
-                    |region| region.ping = status.clone(),
+                    |region| {
+                        if target.is_ipv6() {
+                            region.ping6 = Some(status.clone());
+                        } else {
+                            region.stats.record(&status);
+                            region.ping = status.clone();
+                        }
+                    },
                 );
             }
         }
 
-        if had_updates && self.sort.by == RegionSortBy::Ping {
+        if had_updates && self.sort.is_measured() {
             self.apply_sort();
         }
     }
 
+    /// Pauses the pinger while the window is minimized or hidden, unless pings are being recorded.
+    fn handle_ping_pause(&self, ctx: &egui::Context) {
+        let Some(control) = &self.ping_control else {
+            return;
+        };
+
+        let minimized = ctx.input(|i| i.viewport().minimized.unwrap_or(false));
+        control.set_paused((minimized || self.hidden) && self.recorder.is_none());
+    }
+
+    /// Hides the window to the tray, Wayland windows are minimized since they cannot hide.
+    #[cfg(target_os = "linux")]
+    fn set_hidden(&mut self, ctx: &egui::Context, hidden: bool) {
+        if self.wayland {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Minimized(hidden));
+        } else {
+            self.hidden = hidden;
+            ctx.send_viewport_cmd(egui::ViewportCommand::Visible(!hidden));
+        }
+
+        if !hidden {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
+        }
+    }
+
+    /// Shows the window when enabling blocking first asks for the game executable.
+    #[cfg(target_os = "linux")]
+    fn show_for_exe_selection(&mut self, ctx: &egui::Context) {
+        if self.game_exe.is_none() && self.hidden {
+            self.set_hidden(ctx, false);
+        }
+    }
+
+    /// Hides the window instead of closing it while the tray icon is shown.
+    #[cfg(target_os = "linux")]
+    fn handle_close(&mut self, ctx: &egui::Context) {
+        if self.tray.is_some() && !self.quitting && ctx.input(|i| i.viewport().close_requested()) {
+            ctx.send_viewport_cmd(egui::ViewportCommand::CancelClose);
+            self.set_hidden(ctx, true);
+        }
+    }
+
+    /// Forgets the applied selection once the daemon exited on its own and removed its rules.
+    #[cfg(target_os = "linux")]
+    fn handle_daemon_exit(&mut self) {
+        let Some(removed) = self.monitor.as_ref().and_then(DaemonMonitor::exited) else {
+            return;
+        };
+
+        self.monitor = None;
+        // a crashed daemon leaves its rules, they still apply to the running game
+        if removed {
+            self.applied = None;
+        }
+    }
+
+    /// Runs the actions requested from the tray icon and refreshes what it shows.
+    #[cfg(target_os = "linux")]
+    fn handle_tray(&mut self, ctx: &egui::Context) {
+        while let Some(command) = self.tray.as_mut().and_then(TrayIcon::try_recv) {
+            match command {
+                TrayCommand::ToggleWindow => {
+                    // the minimized state of Wayland windows is unknown, the icon only shows them
+                    let hide = !self.hidden && !self.wayland;
+                    self.set_hidden(ctx, hide);
+                }
+                TrayCommand::Enable => {
+                    self.show_for_exe_selection(ctx);
+                    self.on_enable_btn_click();
+                }
+                TrayCommand::Disable => self.on_disable_btn_click(),
+                TrayCommand::Profile(name) => {
+                    self.show_for_exe_selection(ctx);
+                    self.on_profile_select(name);
+                }
+                TrayCommand::Quit => {
+                    self.quitting = true;
+                    ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                }
+            }
+        }
+
+        let Some(tray) = &mut self.tray else {
+            return;
+        };
+
+        tray.set_state(
+            &self.runtime,
+            TrayState {
+                active: self.applied.is_some(),
+                profiles: self.config.profiles.keys().cloned().collect(),
+                active_profile: self.config.active_profile.clone(),
+                show_window: self.hidden || self.wayland,
+            },
+        );
+    }
+
     fn apply_sort(&mut self) {
         let cmp = self.sort.as_cmp();
 
         self.region_states.sort_by(|_, a, _, b| cmp(a, b));
     }
 
-    fn start_daemon(&self) {
+    fn start_daemon(&mut self) {
         let any_selected = self.region_states.iter().any(|(_, state)| state.selected);
 
         if !any_selected {
-            self.modal_tx
-                .send(Some(ModalDisplay {
-                    level: ModalLevel::Error,
-                    title: "No regions selected".to_string(),
-                    content: "Please select at least one region".to_string(),
-                }))
-                .expect("failed to send modal");
+            self.modal_tx.send(ModalDisplay {
+                level: ModalLevel::Error,
+                title: tr!("no-selection-title"),
+                content: tr!("no-selection-content"),
+            });
 
             return;
         }
@@ -273,11 +646,13 @@ impl App {
             return;
         }
 
-        let blocked_regions = self
-            .region_states
-            .iter()
-            .filter(|&(_, entry)| entry.selected.not())
-            .map(|(key, _)| key.clone());
+        let blocked_prefixes = blocking::blocked_prefixes(
+            self.config.block_mode,
+            self.region_states
+                .values()
+                .map(|entry| (&entry.region, entry.selected)),
+            &self.config.allowlist,
+        );
 
         let game_exe = self
             .game_exe
@@ -287,51 +662,65 @@ impl App {
             .to_string_lossy()
             .to_string();
 
-        if let Err(e) = daemon::start(blocked_regions, game_exe) {
-            self.modal_tx
-                .send(Some(ModalDisplay {
-                    level: ModalLevel::Error,
-                    title: "Cannot enable blocking".to_string(),
-                    content: format!("Failed to activate blocking due to an error:\n\n{e}"),
-                }))
-                .expect("failed to send an error modal");
-        } else {
-            self.modal_tx
-                .send(Some({
-                    ModalDisplay {
-                        level: ModalLevel::Success,
-                        title: "Server list updated".to_string(),
-                        content: "Restart Overwatch to avoid connection issues.".to_string(),
-                    }
-                }))
-                .expect("failed to send a success modal");
+        match daemon::start(blocked_prefixes, game_exe) {
+            Err(e) => self.modal_tx.send(ModalDisplay {
+                level: ModalLevel::Error,
+                title: tr!("enable-failed-title"),
+                content: tr!("enable-failed-content", error = e.to_string()),
+            }),
+            #[cfg_attr(not(target_os = "linux"), allow(unused_variables))]
+            Ok(daemon) => {
+                #[cfg(target_os = "linux")]
+                {
+                    self.monitor = Some(DaemonMonitor::start(
+                        &self.runtime,
+                        daemon,
+                        self.modal_tx.clone(),
+                    ));
+                }
+
+                self.applied = Some(AppliedSelection::new(
+                    &self.region_states,
+                    self.config.block_mode,
+                ));
+
+                // on linux the monitor reports it once the daemon answers
+                #[cfg(not(target_os = "linux"))]
+                self.modal_tx.send(ModalDisplay {
+                    level: ModalLevel::Success,
+                    title: tr!("enabled-title"),
+                    content: tr!("enabled-content"),
+                });
+            }
         }
     }
 
-    fn stop_daemon(&self, silent: bool) -> Result<()> {
+    fn stop_daemon(&mut self, silent: bool) -> Result<()> {
         if let Err(e) = daemon::kill() {
             if silent && let KillError::Refused = e {
                 return Ok(());
             }
-            self.modal_tx
-                .send(Some(ModalDisplay {
-                    level: ModalLevel::Error,
-                    title: "Cannot disable blocking".to_string(),
-                    content: format!("Failed to deactivate blocking due to an error:\n{e}"),
-                }))
-                .expect("failed to send an error modal");
+            self.modal_tx.send(ModalDisplay {
+                level: ModalLevel::Error,
+                title: tr!("disable-failed-title"),
+                content: tr!("disable-failed-content", error = e.to_string()),
+            });
 
             return Err(anyhow!("{}", e.to_string()));
-        } else if !silent {
-            self.modal_tx
-                .send(Some({
-                    ModalDisplay {
-                        level: ModalLevel::Success,
-                        title: "Server blocking disabled".to_string(),
-                        content: "Restart Overwatch for the changes to apply.".to_string(),
-                    }
-                }))
-                .expect("failed to send a success modal");
+        }
+
+        self.applied = None;
+        #[cfg(target_os = "linux")]
+        {
+            self.monitor = None;
+        }
+
+        if !silent {
+            self.modal_tx.send(ModalDisplay {
+                level: ModalLevel::Success,
+                title: tr!("disabled-title"),
+                content: tr!("disabled-content"),
+            });
         }
 
         Ok(())
@@ -341,11 +730,11 @@ impl App {
         self.run_exe_selection(false);
     }
 
-    fn on_disable_btn_click(&self) {
+    fn on_disable_btn_click(&mut self) {
         let _ = self.stop_daemon(false);
     }
 
-    fn on_enable_btn_click(&self) {
+    fn on_enable_btn_click(&mut self) {
         if self.game_exe.is_none() {
             return self.run_exe_selection(true);
         }
@@ -353,19 +742,67 @@ impl App {
         self.start_daemon();
     }
 
-    fn render_bottom_bar(&self, ctx: &egui::Context) {
+    fn on_record_btn_click(&mut self) {
+        if self.recorder.take().is_none() {
+            self.run_recording_selection();
+        }
+    }
+
+    fn render_bottom_bar(&mut self, ctx: &egui::Context) {
         TopBottomPanel::bottom("bottom panel").show(ctx, |ui| {
             ui.horizontal(|ui| {
                 global_theme_preference_switch(ui);
 
+                let record_label = if self.recorder.is_some() {
+                    tr!("record-stop")
+                } else {
+                    tr!("record")
+                };
+                let record_btn = ui.small_button(record_label);
+                let record_btn = match &self.recorder {
+                    Some(recorder) => record_btn.on_hover_text(tr!(
+                        "record-to",
+                        path = recorder.path.display().to_string()
+                    )),
+                    None => record_btn.on_hover_text(tr!("record-hover")),
+                };
+                if record_btn.clicked() {
+                    self.on_record_btn_click();
+                }
+
+                if ui
+                    .small_button(tr!("customize"))
+                    .on_hover_text(tr!("customize-hover"))
+                    .clicked()
+                {
+                    self.on_customize_btn_click();
+                }
+
+                if ui
+                    .small_button(tr!("log"))
+                    .on_hover_text(tr!("log-hover"))
+                    .clicked()
+                {
+                    self.modals.log_open = true;
+                }
+
+                #[cfg(target_os = "linux")]
+                if ui
+                    .small_button(tr!("activity"))
+                    .on_hover_text(tr!("activity-hover"))
+                    .clicked()
+                {
+                    self.activity.open = true;
+                }
+
                 ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
-                    if ui.small_button("enable").clicked() {
+                    if ui.small_button(tr!("enable")).clicked() {
                         self.on_enable_btn_click();
                     }
-                    if ui.small_button("disable").clicked() {
+                    if ui.small_button(tr!("disable")).clicked() {
                         self.on_disable_btn_click();
                     }
-                    if ui.small_button("select game path").clicked() {
+                    if ui.small_button(tr!("select-game-path")).clicked() {
                         self.on_game_path_btn_click();
                     }
                 })
@@ -373,88 +810,1236 @@ impl App {
         });
     }
 
-    fn on_sort_btn_click(&mut self, shift: bool) {
-        if shift {
-            self.sort.toggle_asc();
-        } else {
-            self.sort.cycle_property();
+    /// Changes the configuration and saves it, keeping the settings changed elsewhere.
+    fn update_config(&mut self, update: impl FnOnce(&mut Config)) {
+        let result = Config::load().and_then(|mut config| {
+            update(&mut config);
+            config.save()?;
+            Ok(config)
+        });
+
+        match result {
+            Ok(config) => self.config = config,
+            Err(e) => self.modal_tx.send(ModalDisplay {
+                level: ModalLevel::Error,
+                title: tr!("settings-save-failed-title"),
+                content: tr!("config-save-failed", error = format!("{e:#}")),
+            }),
         }
+    }
 
-        self.apply_sort();
+    fn render_block_mode(&mut self, ui: &mut egui::Ui) {
+        let mut mode = self.config.block_mode;
+
+        ComboBox::from_id_salt("block mode")
+            .selected_text(tr!("block-mode-select", mode = mode.to_string()))
+            .show_ui(ui, |ui| {
+                for option in BlockMode::ALL {
+                    ui.selectable_value(&mut mode, option, option.to_string())
+                        .on_hover_text(option.description());
+                }
+            })
+            .response
+            .on_hover_text(mode.description());
+
+        if mode != self.config.block_mode {
+            self.update_config(|config| config.block_mode = mode);
+        }
     }
 
-    fn render_sort_button(&mut self, ui: &mut egui::Ui) {
-        let image = match (self.sort.by, self.sort.asc) {
-            (RegionSortBy::Name, true) => {
-                include_image!("../assets/icons/arrow-down-a-z.svg")
+    fn render_profile_selector(&mut self, ui: &mut egui::Ui) {
+        let mut selected = None;
+
+        ComboBox::from_id_salt("profile")
+            .selected_text(tr!(
+                "profile-select",
+                name = self
+                    .config
+                    .active_profile
+                    .clone()
+                    .unwrap_or_else(|| tr!("profile-none"))
+            ))
+            .show_ui(ui, |ui| {
+                for name in self.config.profiles.keys() {
+                    let active = self.config.active_profile.as_ref() == Some(name);
+                    if ui.selectable_label(active, name).clicked() {
+                        selected = Some(name.clone());
+                    }
+                }
+            })
+            .response
+            .on_hover_text(tr!("profile-select-hover"));
+
+        if ui
+            .small_button(tr!("profiles"))
+            .on_hover_text(tr!("profiles-hover"))
+            .clicked()
+        {
+            self.profiles.open = true;
+        }
+
+        if let Some(name) = selected {
+            self.on_profile_select(name);
+        }
+    }
+
+    fn on_profile_select(&mut self, name: String) {
+        let Some(profile) = self.config.profiles.get(&name).cloned() else {
+            return;
+        };
+
+        for (key, entry) in self.region_states.iter_mut() {
+            entry.selected = profile.regions.contains(key);
+        }
+
+        self.update_config(|config| {
+            config.block_mode = profile.block_mode;
+            config.active_profile = Some(name);
+        });
+
+        self.on_enable_btn_click();
+    }
+
+    fn on_profile_action(&mut self, action: ProfileAction) {
+        let current = Profile {
+            regions: self
+                .region_states
+                .iter()
+                .filter(|(_, entry)| entry.selected)
+                .map(|(key, _)| key.clone())
+                .collect(),
+            block_mode: self.config.block_mode,
+        };
+
+        let checked = match &action {
+            ProfileAction::Create => {
+                ProfileManager::check_name(&self.config, &self.profiles.new_name)
             }
-            (RegionSortBy::Name, false) => {
-                include_image!("../assets/icons/arrow-up-z-a.svg")
+            ProfileAction::Rename(old, new) if old != new.trim() => {
+                ProfileManager::check_name(&self.config, new)
             }
-            (RegionSortBy::Ping, true) => {
-                include_image!("../assets/icons/arrow-down-0-1.svg")
+            _ => Ok(()),
+        };
+        if let Err(e) = checked {
+            self.profiles.error = Some(e.to_string());
+            return;
+        }
+        self.profiles.error = None;
+
+        match action {
+            ProfileAction::Create => {
+                let name = std::mem::take(&mut self.profiles.new_name)
+                    .trim()
+                    .to_string();
+                self.update_config(|config| {
+                    config.profiles.insert(name.clone(), current);
+                    config.active_profile = Some(name);
+                });
             }
-            (RegionSortBy::Ping, false) => {
-                include_image!("../assets/icons/arrow-up-1-0.svg")
+            ProfileAction::Save(name) => self.update_config(|config| {
+                config.profiles.insert(name.clone(), current);
+                config.active_profile = Some(name);
+            }),
+            ProfileAction::Rename(old, new) => {
+                let new = new.trim().to_string();
+                self.profiles.renaming = None;
+                self.update_config(|config| {
+                    if let Some(profile) = config.profiles.remove(&old) {
+                        config.profiles.insert(new.clone(), profile);
+                    }
+                    if config.active_profile.as_ref() == Some(&old) {
+                        config.active_profile = Some(new);
+                    }
+                });
             }
+            ProfileAction::Delete(name) => self.update_config(|config| {
+                config.profiles.remove(&name);
+                if config.active_profile.as_ref() == Some(&name) {
+                    config.active_profile = None;
+                }
+            }),
+        }
+    }
+
+    fn render_profiles(&mut self, ctx: &egui::Context) {
+        let mut open = self.profiles.open;
+        let mut action = None;
+
+        Window::new(tr!("profiles-title"))
+            .id("profiles".into())
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let ProfileManager {
+                    new_name,
+                    renaming,
+                    error,
+                    ..
+                } = &mut self.profiles;
+
+                if self.config.profiles.is_empty() {
+                    ui.label(tr!("profiles-empty"));
+                }
+
+                let mut cancel_rename = false;
+                Grid::new("profiles").num_columns(2).show(ui, |ui| {
+                    for (name, profile) in &self.config.profiles {
+                        match renaming {
+                            Some((old, new)) if old == name => {
+                                let edit = ui.text_edit_singleline(new);
+                                let confirm = ui.input(|i| i.key_pressed(egui::Key::Enter));
+                                ui.horizontal(|ui| {
+                                    if ui.small_button(tr!("ok")).clicked()
+                                        || (edit.lost_focus() && confirm)
+                                    {
+                                        action =
+                                            Some(ProfileAction::Rename(old.clone(), new.clone()));
+                                    }
+                                    cancel_rename = ui.small_button(tr!("cancel")).clicked();
+                                });
+                            }
+                            _ => {
+                                ui.label(name).on_hover_text(tr!(
+                                    "profile-summary",
+                                    regions = profile.regions.len(),
+                                    mode = profile.block_mode.to_string()
+                                ));
+                                ui.horizontal(|ui| {
+                                    if ui
+                                        .small_button(tr!("save"))
+                                        .on_hover_text(tr!("profile-save-hover"))
+                                        .clicked()
+                                    {
+                                        action = Some(ProfileAction::Save(name.clone()));
+                                    }
+                                    if ui.small_button(tr!("rename")).clicked() {
+                                        *renaming = Some((name.clone(), name.clone()));
+                                    }
+                                    if ui.small_button(tr!("delete")).clicked() {
+                                        action = Some(ProfileAction::Delete(name.clone()));
+                                    }
+                                });
+                            }
+                        }
+                        ui.end_row();
+                    }
+                });
+
+                if cancel_rename {
+                    *renaming = None;
+                }
+
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    ui.add(TextEdit::singleline(new_name).hint_text(tr!("profile-new-hint")));
+                    if ui
+                        .small_button(tr!("create"))
+                        .on_hover_text(tr!("profile-create-hover"))
+                        .clicked()
+                    {
+                        action = Some(ProfileAction::Create);
+                    }
+                });
+
+                if let Some(error) = error {
+                    ui.colored_label(ui.visuals().error_fg_color, error.as_str());
+                }
+            });
+
+        self.profiles.open = open;
+
+        if let Some(action) = action {
+            self.on_profile_action(action);
+        }
+    }
+
+    fn render_pending_changes(&mut self, ui: &mut egui::Ui) {
+        let Some(applied) = &self.applied else {
+            return;
         };
 
-        let button = ImageButton::new(image)
-            .tint(ui.visuals().text_color())
-            .frame(false)
-            .ui(ui)
-            .on_hover_text(format!(
-                "Sorted by {} ({}).\n\u{2022} Click to sort by {}.\n\u{2022} Right Click or Shift+Click to change ordering.",
-                self.sort.by,
-                self.sort.ordering_name(),
-                self.sort.next_property(),
-            ));
+        let pending = applied.pending_count(&self.region_states);
+        let mode_changed = applied.block_mode != self.config.block_mode;
+        if pending == 0 && !mode_changed {
+            return;
+        }
+
+        let text = match pending {
+            0 => tr!("pending-mode"),
+            count => tr!("pending-regions", count = count),
+        };
 
-        if button.clicked() || button.secondary_clicked() {
-            self.on_sort_btn_click(button.secondary_clicked() || ui.input(|i| i.modifiers.shift));
+        ui.horizontal(|ui| {
+            if ui
+                .button(tr!("apply-changes"))
+                .on_hover_text(tr!("apply-changes-hover"))
+                .clicked()
+            {
+                self.on_enable_btn_click();
+            }
+            if ui
+                .small_button(tr!("revert"))
+                .on_hover_text(tr!("revert-hover"))
+                .clicked()
+            {
+                self.on_revert_btn_click();
+            }
+            ui.label(RichText::new(text).small());
+        });
+    }
+
+    fn on_revert_btn_click(&mut self) {
+        let Some(applied) = &self.applied else {
+            return;
+        };
+
+        for (key, entry) in self.region_states.iter_mut() {
+            entry.selected = applied.regions.contains(key);
+        }
+
+        let block_mode = applied.block_mode;
+        if block_mode != self.config.block_mode {
+            self.update_config(|config| config.block_mode = block_mode);
+        }
+    }
+
+    fn render_filter_bar(&mut self, ui: &mut egui::Ui) {
+        let filter = &mut self.filter;
+        let focus = ui.input_mut(|i| i.consume_key(Modifiers::COMMAND, Key::F));
+
+        ui.horizontal(|ui| {
+            let edit = ui
+                .add(
+                    TextEdit::singleline(&mut filter.text)
+                        .hint_text(tr!("filter-hint"))
+                        .desired_width(ui.available_width() - 30.),
+                )
+                .on_hover_text(tr!("filter-hover"));
+            if focus {
+                edit.request_focus();
+            }
+            if edit.lost_focus() && ui.input(|i| i.key_pressed(Key::Escape)) {
+                filter.text.clear();
+            }
+
+            if ui
+                .add_enabled(filter.is_active(), egui::Button::new("✖").small())
+                .on_hover_text(tr!("filter-clear-hover"))
+                .clicked()
+            {
+                *filter = RegionFilter::default();
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.toggle_value(&mut filter.selected_only, tr!("filter-selected"))
+                .on_hover_text(tr!("filter-selected-hover"));
+            ui.toggle_value(&mut filter.reachable_only, tr!("filter-reachable"))
+                .on_hover_text(tr!("filter-reachable-hover"));
+
+            let mut below = filter.max_ping.is_some();
+            ui.toggle_value(&mut below, tr!("filter-below"))
+                .on_hover_text(tr!("filter-below-hover"));
+            if below {
+                let max_ping = filter.max_ping.get_or_insert(100);
+                ui.add(DragValue::new(max_ping).range(1..=1000).suffix(" ms"));
+            } else {
+                filter.max_ping = None;
+            }
+        });
+    }
+
+    fn render_grouping(&mut self, ui: &mut egui::Ui) {
+        ComboBox::from_id_salt("region grouping")
+            .selected_text(tr!("group-select", grouping = self.grouping.to_string()))
+            .show_ui(ui, |ui| {
+                for option in RegionGrouping::ALL {
+                    ui.selectable_value(&mut self.grouping, option, option.to_string());
+                }
+            })
+            .response
+            .on_hover_text(tr!("group-hover"));
+    }
+
+    /// Region whose prefixes contain `addr`.
+    fn find_region(&self, addr: IpAddr) -> Option<&RegionEntry> {
+        self.region_states
+            .values()
+            .find(|entry| entry.region.prefixes.iter().any(|net| net.contains(addr)))
+    }
+
+    #[cfg(target_os = "linux")]
+    fn render_activity(&mut self, ctx: &egui::Context) {
+        if !self.activity.open {
+            return;
+        }
+
+        ctx.request_repaint_after(self.activity.poll());
+
+        let mut open = true;
+
+        Window::new(tr!("activity-title"))
+            .id("activity".into())
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(true)
+            .vscroll(true)
+            .show(ctx, |ui| {
+                if let Some(error) = &self.activity.error {
+                    ui.colored_label(ui.visuals().error_fg_color, error);
+                }
+
+                ui.strong(tr!("activity-dropped"));
+
+                let mut dropped = IndexMap::<String, (u64, u64)>::new();
+                for counter in &self.activity.counters {
+                    let name = match &counter.region {
+                        Some(key) => self
+                            .region_states
+                            .get(key)
+                            .map_or_else(|| key.clone(), |entry| i18n::region_name(&entry.region)),
+                        None => tr!("activity-unlisted"),
+                    };
+
+                    let (packets, bytes) = dropped.entry(name).or_default();
+                    *packets += counter.packets;
+                    *bytes += counter.bytes;
+                }
+                dropped.sort_by(|_, a, _, b| b.cmp(a));
+
+                if dropped.is_empty() {
+                    ui.label(tr!("activity-nothing-blocked"));
+                } else {
+                    Grid::new("dropped").striped(true).show(ui, |ui| {
+                        for (name, (packets, bytes)) in dropped {
+                            ui.label(name);
+                            ui.label(tr!("activity-packets", packets = packets));
+                            ui.label(observer::format_bytes(bytes));
+                            ui.end_row();
+                        }
+                    });
+                }
+
+                ui.separator();
+                ui.strong(tr!("activity-connections"));
+
+                if self.activity.connections.is_empty() {
+                    ui.label(tr!("activity-no-connection"));
+                    return;
+                }
+
+                let unknown = self
+                    .activity
+                    .connections
+                    .iter()
+                    .filter(|c| self.find_region(c.remote.ip()).is_none())
+                    .map(|c| c.remote.ip())
+                    .unique()
+                    .collect_vec();
+
+                if !unknown.is_empty()
+                    && ui
+                        .button(tr!("activity-copy-unknown", count = unknown.len()))
+                        .on_hover_text(tr!("activity-copy-unknown-hover"))
+                        .clicked()
+                {
+                    ctx.copy_text(unknown.iter().map(|ip| IpNetwork::from(*ip)).join("\n"));
+                }
+
+                Grid::new("connections").striped(true).show(ui, |ui| {
+                    for connection in &self.activity.connections {
+                        ui.label(connection.remote.to_string());
+                        match self.find_region(connection.remote.ip()) {
+                            Some(entry) => ui.label(i18n::region_name(&entry.region)),
+                            None => ui
+                                .colored_label(ui.visuals().warn_fg_color, tr!("activity-unknown")),
+                        };
+
+                        let ago = connection.last_seen.elapsed().unwrap_or_default();
+                        ui.label(tr!(
+                            "activity-ago",
+                            duration =
+                                humantime::format_duration(Duration::from_secs(ago.as_secs()))
+                                    .to_string()
+                        ));
+                        ui.end_row();
+                    }
+                });
+            });
+
+        self.activity.open = open;
+    }
+
+    fn on_customize_btn_click(&mut self) {
+        match Config::load() {
+            Ok(config) => self.custom_editor.open(&config),
+            Err(e) => self.modal_tx.send(ModalDisplay {
+                level: ModalLevel::Error,
+                title: tr!("custom-load-failed-title"),
+                content: tr!("config-load-failed", error = format!("{e:#}")),
+            }),
+        }
+    }
+
+    fn on_custom_regions_save(&mut self) {
+        let result = Config::load().and_then(|mut config| {
+            if !self.custom_editor.apply(&mut config) {
+                return Ok(None);
+            }
+            config.save()?;
+            Ok(Some(config))
+        });
+
+        match result {
+            Ok(Some(config)) => {
+                self.config = config;
+                self.modal_tx.send(ModalDisplay {
+                    level: ModalLevel::Info,
+                    title: tr!("custom-saved-title"),
+                    content: tr!("restart-for-regions"),
+                });
+            }
+            Ok(None) => {}
+            Err(e) => self.modal_tx.send(ModalDisplay {
+                level: ModalLevel::Error,
+                title: tr!("custom-save-failed-title"),
+                content: tr!("config-save-failed", error = format!("{e:#}")),
+            }),
+        }
+    }
+
+    fn render_custom_editor(&mut self, ctx: &egui::Context) {
+        let mut open = self.custom_editor.open;
+        let mut save = false;
+
+        Window::new(tr!("custom-title"))
+            .id("custom regions".into())
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(true)
+            .vscroll(true)
+            .show(ctx, |ui| {
+                let CustomEditor {
+                    regions,
+                    overrides,
+                    published,
+                    errors,
+                    warnings,
+                    ..
+                } = &mut self.custom_editor;
+
+                ui.strong(tr!("custom-title"));
+
+                let mut removed = None;
+                for (i, draft) in regions.iter_mut().enumerate() {
+                    ui.push_id(i, |ui| {
+                        Grid::new("custom region").num_columns(2).show(ui, |ui| {
+                            ui.label(tr!("custom-name"));
+                            ui.text_edit_singleline(&mut draft.name);
+                            ui.end_row();
+                            ui.label(tr!("custom-code"));
+                            ui.text_edit_singleline(&mut draft.code);
+                            ui.end_row();
+                            ui.label(tr!("custom-ping"));
+                            ui.text_edit_singleline(&mut draft.ping);
+                            ui.end_row();
+                            ui.label(tr!("custom-ping6"));
+                            ui.add(
+                                TextEdit::singleline(&mut draft.ping6)
+                                    .hint_text(tr!("custom-optional")),
+                            );
+                            ui.end_row();
+                            ui.label(tr!("custom-continent"));
+                            ComboBox::from_id_salt("continent")
+                                .selected_text(draft.continent.to_string())
+                                .show_ui(ui, |ui| {
+                                    for continent in Continent::ALL {
+                                        ui.selectable_value(
+                                            &mut draft.continent,
+                                            continent,
+                                            continent.to_string(),
+                                        );
+                                    }
+                                });
+                            ui.end_row();
+                            ui.label(tr!("custom-prefixes"));
+                            ui.add(
+                                TextEdit::multiline(&mut draft.prefixes)
+                                    .hint_text(tr!("custom-cidr-hint"))
+                                    .desired_rows(3),
+                            );
+                            ui.end_row();
+                        });
+
+                        if ui.small_button(tr!("custom-remove")).clicked() {
+                            removed = Some(i);
+                        }
+                    });
+                    ui.separator();
+                }
+                if let Some(i) = removed {
+                    regions.remove(i);
+                }
+
+                if ui.button(tr!("custom-add-region")).clicked() {
+                    regions.push(Default::default());
+                }
+
+                ui.separator();
+                ui.strong(tr!("custom-overrides"));
+
+                let mut removed = None;
+                for (key, draft) in overrides.iter_mut() {
+                    let name = published
+                        .iter()
+                        .find(|r| &r.key == key)
+                        .map_or(key.as_str(), |r| &r.name);
+
+                    ui.push_id(key, |ui| {
+                        ui.horizontal(|ui| {
+                            ui.label(name);
+                            if ui.small_button(tr!("custom-remove")).clicked() {
+                                removed = Some(key.clone());
+                            }
+                        });
+
+                        Grid::new("prefix override").num_columns(2).show(ui, |ui| {
+                            ui.label(tr!("custom-extra"));
+                            ui.add(
+                                TextEdit::multiline(&mut draft.extra)
+                                    .hint_text(tr!("custom-cidr-hint"))
+                                    .desired_rows(2),
+                            );
+                            ui.end_row();
+                            ui.label(tr!("custom-excluded"));
+                            ui.add(
+                                TextEdit::multiline(&mut draft.excluded)
+                                    .hint_text(tr!("custom-cidr-hint"))
+                                    .desired_rows(2),
+                            );
+                            ui.end_row();
+                        });
+                    });
+                    ui.separator();
+                }
+                if let Some(key) = removed {
+                    overrides.shift_remove(&key);
+                }
+
+                let mut added = None;
+                ComboBox::from_id_salt("override region")
+                    .selected_text(tr!("custom-override-region"))
+                    .show_ui(ui, |ui| {
+                        for region in published.iter() {
+                            if !overrides.contains_key(&region.key)
+                                && ui
+                                    .selectable_label(false, i18n::region_name(region))
+                                    .clicked()
+                            {
+                                added = Some(region.key.clone());
+                            }
+                        }
+                    });
+                if let Some(key) = added {
+                    overrides.insert(key, Default::default());
+                }
+
+                if !errors.is_empty() || !warnings.is_empty() {
+                    ui.separator();
+                }
+                for error in errors.iter() {
+                    ui.colored_label(ui.visuals().error_fg_color, error);
+                }
+                for warning in warnings.iter() {
+                    ui.colored_label(ui.visuals().warn_fg_color, warning);
+                }
+
+                ui.separator();
+                if ui.button(tr!("save")).clicked() {
+                    save = true;
+                }
+            });
+
+        self.custom_editor.open = open;
+
+        if save {
+            self.on_custom_regions_save();
+        }
+    }
+
+    /// Changes the sorting, reorders the list and remembers the choice.
+    fn on_sort_change(&mut self, sort: RegionSorting) {
+        self.sort = sort.clone();
+        self.apply_sort();
+        self.update_config(|config| config.sort = sort);
+    }
+
+    fn render_sort_button(&mut self, ui: &mut egui::Ui) {
+        let image = match (self.sort.by.is_numeric(), self.sort.asc) {
+            (false, true) => include_image!("../assets/icons/arrow-down-a-z.svg"),
+            (false, false) => include_image!("../assets/icons/arrow-up-z-a.svg"),
+            (true, true) => include_image!("../assets/icons/arrow-down-0-1.svg"),
+            (true, false) => include_image!("../assets/icons/arrow-up-1-0.svg"),
+        };
+
+        let mut sort = self.sort.clone();
+
+        let menu = ui.menu_image_button(
+            egui::Image::new(image).tint(ui.visuals().text_color()),
+            |ui| {
+                ui.label(tr!("sort-by"));
+                for by in RegionSortBy::ALL {
+                    ui.radio_value(&mut sort.by, by, by.to_string());
+                }
+
+                ui.separator();
+                ui.checkbox(&mut sort.asc, tr!("sort-ascending"));
+
+                ui.separator();
+                ui.label(tr!("sort-then-by"));
+                ui.radio_value(&mut sort.then_by, None, tr!("sort-nothing"));
+                for by in RegionSortBy::ALL {
+                    if by != sort.by {
+                        ui.radio_value(&mut sort.then_by, Some(by), by.to_string());
+                    }
+                }
+            },
+        );
+
+        let button = menu.response.on_hover_text(tr!(
+            "sort-hover",
+            by = self.sort.by.to_string(),
+            ordering = self.sort.ordering_name(),
+        ));
+
+        if button.secondary_clicked() {
+            sort.toggle_asc();
+        }
+
+        if sort.then_by == Some(sort.by) {
+            sort.then_by = None;
+        }
+
+        if sort != self.sort {
+            self.on_sort_change(sort);
+        }
+    }
+
+    fn on_bulk_selection(&mut self, bulk: BulkSelection) {
+        bulk.apply(&mut self.region_states, &self.filter);
+    }
+
+    fn render_bulk_selection(&mut self, ui: &mut egui::Ui) {
+        let mut bulk = None;
+
+        // leave the shortcuts to text fields while they are focused
+        if !ui.ctx().wants_keyboard_input() {
+            bulk = ui.input_mut(|i| {
+                if i.consume_key(Modifiers::COMMAND | Modifiers::SHIFT, Key::A) {
+                    Some(BulkSelection::None)
+                } else if i.consume_key(Modifiers::COMMAND, Key::A) {
+                    Some(BulkSelection::All)
+                } else if i.consume_key(Modifiers::COMMAND, Key::I) {
+                    Some(BulkSelection::Invert)
+                } else if i.consume_key(Modifiers::COMMAND, Key::B) {
+                    Some(BulkSelection::Best(self.auto_select.best_n))
+                } else {
+                    None
+                }
+            });
+        }
+
+        ui.menu_button(tr!("select"), |ui| {
+            let actions = [
+                (tr!("select-all"), "Ctrl+A", BulkSelection::All),
+                (tr!("select-none"), "Ctrl+Shift+A", BulkSelection::None),
+                (tr!("select-invert"), "Ctrl+I", BulkSelection::Invert),
+            ];
+            for (label, shortcut, action) in actions {
+                if ui
+                    .add(egui::Button::new(label).shortcut_text(shortcut))
+                    .clicked()
+                {
+                    bulk = Some(action);
+                    ui.close_menu();
+                }
+            }
+
+            ui.horizontal(|ui| {
+                if ui
+                    .add(egui::Button::new(tr!("select-best")).shortcut_text("Ctrl+B"))
+                    .on_hover_text(tr!("select-best-hover"))
+                    .clicked()
+                {
+                    bulk = Some(BulkSelection::Best(self.auto_select.best_n));
+                    ui.close_menu();
+                }
+                ui.add(DragValue::new(&mut self.auto_select.best_n).range(1..=100));
+            });
+        })
+        .response
+        .on_hover_text(tr!("select-hover"));
+
+        if let Some(bulk) = bulk {
+            self.on_bulk_selection(bulk);
         }
     }
 
     fn render_central_panel(&mut self, ctx: &egui::Context) {
         CentralPanel::default().show(ctx, |ui| {
             ui.horizontal(|ui| {
-                ui.label("Select desired matchmaking regions:");
+                ui.label(tr!("regions-heading"));
 
                 ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
                     self.render_sort_button(ui);
+                    self.render_bulk_selection(ui);
+
+                    if ui
+                        .small_button(tr!("auto"))
+                        .on_hover_text(tr!("auto-hover"))
+                        .clicked()
+                    {
+                        self.auto_select.open = true;
+                    }
                 });
             });
 
+            ui.horizontal(|ui| {
+                self.render_profile_selector(ui);
+            });
+
+            ui.horizontal(|ui| {
+                self.render_block_mode(ui);
+                self.render_grouping(ui);
+            });
+
+            self.render_filter_bar(ui);
+            self.render_pending_changes(ui);
+
             ui.separator();
 
+            let mut traceroute_target = None;
+            let mut apply = false;
+
             ScrollArea::vertical().show(ui, |ui| {
-                for (_, entry) in self.region_states.iter_mut() {
-                    let widget = widgets::prefix_widget(
-                        ui,
-                        &entry.region.name,
-                        &entry.region.code,
-                        entry.selected,
-                        &entry.ping,
+                let mut responses = Vec::new();
+
+                if !self
+                    .region_states
+                    .values()
+                    .any(|entry| self.filter.matches(entry))
+                {
+                    ui.label(tr!("no-region-matches"));
+                } else if self.grouping == RegionGrouping::None {
+                    for (key, entry) in self.region_states.iter_mut() {
+                        if self.filter.matches(entry) {
+                            let pending = self
+                                .applied
+                                .as_ref()
+                                .is_some_and(|applied| applied.is_pending(key, entry));
+                            responses.push(Self::render_region(
+                                ui,
+                                key,
+                                entry,
+                                pending,
+                                &mut traceroute_target,
+                            ));
+                        }
+                    }
+                } else {
+                    responses = self.render_region_groups(ui, &mut traceroute_target);
+                }
+
+                apply = Self::handle_region_keys(ui, &responses);
+            });
+
+            if let Some((key, target)) = traceroute_target {
+                self.start_traceroute(ctx, key, target);
+            }
+
+            if apply {
+                self.on_enable_btn_click();
+            }
+        });
+    }
+
+    /// Keyboard handling of the region list, arrow keys move between regions and space toggles
+    /// the focused one.
+    ///
+    /// Returns whether Enter was pressed on a region to apply the selection.
+    fn handle_region_keys(ui: &mut egui::Ui, responses: &[egui::Response]) -> bool {
+        let focused = responses.iter().find(|response| response.has_focus());
+
+        if focused.is_none()
+            && ui.memory(|m| m.focused().is_none())
+            && ui.input_mut(|i| i.consume_key(Modifiers::NONE, Key::ArrowDown))
+            && let Some(first) = responses.first()
+        {
+            first.request_focus();
+        }
+
+        if let Some(focused) = focused
+            && focused.gained_focus()
+        {
+            focused.scroll_to_me(None);
+        }
+
+        focused.is_some() && ui.input(|i| i.key_pressed(Key::Enter))
+    }
+
+    /// Renders a region of the list, `traceroute_target` is set when a traceroute is requested.
+    ///
+    /// `pending` marks a region whose selection differs from the running blocking.
+    fn render_region(
+        ui: &mut egui::Ui,
+        key: &str,
+        entry: &mut RegionEntry,
+        pending: bool,
+        traceroute_target: &mut Option<(String, IpAddr)>,
+    ) -> egui::Response {
+        let widget = widgets::prefix_widget(
+            ui,
+            &i18n::region_name(&entry.region),
+            &entry.region.code,
+            entry.selected,
+            &entry.ping,
+            entry.ping6.as_ref(),
+            pending,
+        );
+
+        let widget = if pending {
+            widget.on_hover_text(tr!("region-pending-hover"))
+        } else {
+            widget
+        };
+
+        // Enter applies the selection instead of toggling the focused region
+        let enter = widget.has_focus() && ui.input(|i| i.key_pressed(Key::Enter));
+        if widget.clicked() && !enter {
+            entry.selected = !entry.selected;
+        }
+
+        widget.context_menu(|ui| {
+            for target in entry.region.ping_targets() {
+                let label = if target.is_ipv4() {
+                    tr!("traceroute")
+                } else {
+                    tr!("traceroute-v6")
+                };
+
+                if ui.button(label).clicked() {
+                    *traceroute_target = Some((key.to_string(), target));
+                    ui.close_menu();
+                }
+            }
+        });
+
+        widget
+    }
+
+    /// Renders the regions under collapsible headers, keeping the current sorting in each group.
+    fn render_region_groups(
+        &mut self,
+        ui: &mut egui::Ui,
+        traceroute_target: &mut Option<(String, IpAddr)>,
+    ) -> Vec<egui::Response> {
+        let mut responses = Vec::new();
+        let groups = self
+            .region_states
+            .iter()
+            .filter(|(_, entry)| self.filter.matches(entry))
+            .filter_map(|(key, entry)| Some((self.grouping.group_of(&entry.region)?, key.clone())))
+            .into_group_map()
+            .into_iter()
+            .sorted();
+
+        let mut only_group = None;
+        let mut group_ids = Vec::new();
+
+        for ((_, name), keys) in groups {
+            let id = ui.make_persistent_id(("region group", self.grouping, &name));
+            group_ids.push((id, name.clone()));
+
+            let selected = keys
+                .iter()
+                .filter(|key| self.region_states[*key].selected)
+                .count();
+
+            CollapsingState::load_with_default_open(ui.ctx(), id, true)
+                .show_header(ui, |ui| {
+                    let mut all = selected == keys.len();
+                    let checkbox = ui
+                        .add(
+                            Checkbox::new(&mut all, format!("{name} ({selected}/{})", keys.len()))
+                                .indeterminate(selected > 0 && selected < keys.len()),
+                        )
+                        .on_hover_text(tr!("group-select-hover"));
+
+                    if checkbox.clicked() {
+                        for key in &keys {
+                            self.region_states[key].selected = all;
+                        }
+                    }
+
+                    checkbox.context_menu(|ui| {
+                        if ui.button(tr!("group-show-only")).clicked() {
+                            only_group = Some(name.clone());
+                            ui.close_menu();
+                        }
+                        if ui.button(tr!("group-show-all")).clicked() {
+                            only_group = Some(String::new());
+                            ui.close_menu();
+                        }
+                    });
+                })
+                .body(|ui| {
+                    for key in &keys {
+                        let entry = &mut self.region_states[key];
+                        let pending = self
+                            .applied
+                            .as_ref()
+                            .is_some_and(|applied| applied.is_pending(key, entry));
+                        responses.push(Self::render_region(
+                            ui,
+                            key,
+                            entry,
+                            pending,
+                            traceroute_target,
+                        ));
+                    }
+                });
+        }
+
+        if let Some(only_group) = only_group {
+            for (id, name) in group_ids {
+                let mut state = CollapsingState::load_with_default_open(ui.ctx(), id, true);
+                state.set_open(only_group.is_empty() || name == only_group);
+                state.store(ui.ctx());
+            }
+        }
+
+        responses
+    }
+
+    fn on_auto_select_apply(&mut self) {
+        let Some(proposal) = self.auto_select.proposal.take() else {
+            return;
+        };
+
+        for (key, entry) in self.region_states.iter_mut() {
+            entry.selected = proposal.contains(key);
+        }
+
+        self.on_enable_btn_click();
+    }
+
+    fn render_auto_select(&mut self, ctx: &egui::Context) {
+        self.auto_select.tick(&self.region_states);
+
+        if let Some(delay) = self.auto_select.next_evaluation() {
+            ctx.request_repaint_after(delay);
+        }
+
+        let mut open = self.auto_select.open;
+        let mut apply = false;
+
+        Window::new(tr!("auto-title"))
+            .id("auto select".into())
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let settings = &mut self.auto_select;
+
+                ui.horizontal(|ui| {
+                    ui.radio_value(
+                        &mut settings.mode,
+                        AutoSelectMode::Threshold,
+                        tr!("auto-below"),
+                    );
+                    ui.add(
+                        DragValue::new(&mut settings.threshold_ms)
+                            .range(1..=1000)
+                            .suffix(" ms"),
+                    );
+                });
+                ui.horizontal(|ui| {
+                    ui.radio_value(&mut settings.mode, AutoSelectMode::Best, tr!("auto-best"));
+                    ui.add(DragValue::new(&mut settings.best_n).range(1..=100));
+                    ui.label(tr!("auto-regions"));
+                });
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut settings.periodic, tr!("auto-periodic"));
+                    ui.add(
+                        DragValue::new(&mut settings.period_mins)
+                            .range(1..=240)
+                            .suffix(" min"),
                     );
+                });
 
-                    if widget.clicked() {
-                        entry.selected = !entry.selected;
+                if ui.button(tr!("auto-preview")).clicked() {
+                    settings.propose(&self.region_states);
+                }
+
+                let Some(proposal) = &settings.proposal else {
+                    return;
+                };
+
+                ui.separator();
+
+                if proposal.is_empty() {
+                    ui.label(tr!("auto-no-match"));
+                } else {
+                    for (key, entry) in &self.region_states {
+                        let proposed = proposal.contains(key);
+                        let text = match (entry.selected, proposed) {
+                            (false, true) => format!("+ {}", i18n::region_name(&entry.region)),
+                            (true, false) => format!("- {}", i18n::region_name(&entry.region)),
+                            (true, true) => format!("  {}", i18n::region_name(&entry.region)),
+                            (false, false) => continue,
+                        };
+                        let text = RichText::new(text).monospace();
+
+                        if entry.selected == proposed {
+                            ui.label(text);
+                        } else {
+                            ui.label(text.strong());
+                        }
                     }
                 }
+
+                let empty = proposal.is_empty();
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(!empty, egui::Button::new(tr!("auto-apply")))
+                        .on_hover_text(tr!("auto-apply-hover"))
+                        .clicked()
+                    {
+                        apply = true;
+                    }
+                    if ui.button(tr!("auto-discard")).clicked() {
+                        settings.proposal = None;
+                    }
+                });
             });
-        });
+
+        self.auto_select.open = open;
+
+        if apply {
+            self.on_auto_select_apply();
+        }
     }
 
-    fn render_modal(&mut self, ctx: &egui::Context) {
-        let mut clear_modal = false;
+    fn start_traceroute(&mut self, ctx: &egui::Context, key: String, target: IpAddr) {
+        let ctx = ctx.clone();
+
+        self.traceroute = Some(Traceroute::start(&self.runtime, key, target, move || {
+            ctx.request_repaint()
+        }));
+    }
+
+    fn render_traceroute(&mut self, ctx: &egui::Context) {
+        let Some(traceroute) = &self.traceroute else {
+            return;
+        };
+
+        let name = i18n::region_name(&self.region_states[&traceroute.key].region);
+        let mut open = true;
+
+        Window::new(tr!("traceroute-title", name = name))
+            .id("traceroute".into())
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(true)
+            .show(ctx, |ui| {
+                let state = traceroute.state();
+
+                ui.label(format!(
+                    "{} ({})",
+                    traceroute.target,
+                    match state.reached {
+                        Some(hops) => tr!("traceroute-reached", hops = hops),
+                        None if state.done => tr!("traceroute-not-reached"),
+                        None => tr!("traceroute-running"),
+                    }
+                ));
 
-        if let Some(msg) = &*self.modal_rx.borrow() {
-            modal::show_modal(ctx, msg, || clear_modal = true);
+                if let Some(error) = &state.error {
+                    ui.colored_label(ui.visuals().error_fg_color, error);
+                }
+
+                ui.separator();
+
+                ScrollArea::vertical().show(ui, |ui| {
+                    Grid::new("traceroute hops").striped(true).show(ui, |ui| {
+                        for (ttl, hop) in state.route() {
+                            ui.label(ttl.to_string());
+                            ui.label(hop.addr.map_or("*".to_string(), |addr| addr.to_string()));
+                            ui.label(hop.rtt.map_or("-".to_string(), |rtt| {
+                                format!("{:.1} ms", rtt.as_secs_f64() * 1000.)
+                            }));
+                            ui.label(hop.hostname.as_deref().unwrap_or_default());
+                            ui.end_row();
+                        }
+                    });
+                });
+            });
+
+        if !open {
+            self.traceroute = None;
         }
+    }
+
+    fn render_log(&mut self, ctx: &egui::Context) {
+        let mut open = self.modals.log_open;
+        let mut clear = false;
+
+        Window::new(tr!("log-title"))
+            .id("log".into())
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(true)
+            .default_size(vec2(280., 240.))
+            .show(ctx, |ui| {
+                if self.modals.log.is_empty() {
+                    ui.label(tr!("log-empty"));
+                    return;
+                }
+
+                if ui.small_button(tr!("log-clear")).clicked() {
+                    clear = true;
+                }
+                ui.separator();
+
+                ScrollArea::vertical()
+                    .auto_shrink(false)
+                    .stick_to_bottom(true)
+                    .show(ui, |ui| {
+                        for entry in &self.modals.log {
+                            ui.horizontal(|ui| {
+                                ui.weak(entry.time.format("%H:%M:%S").to_string());
+                                modal::level_icon(ui, &entry.msg.level);
+                                ui.strong(&entry.msg.title);
+                            });
+                            if !entry.msg.content.is_empty() {
+                                ui.label(&entry.msg.content);
+                            }
+                            ui.add_space(4.);
+                        }
+                    });
+            });
 
-        if clear_modal {
-            self.modal_tx.send(None).ok();
+        if clear {
+            self.modals.log.clear();
         }
+        self.modals.log_open = open;
+    }
+
+    fn render_modal(&mut self, ctx: &egui::Context) {
+        self.modals.receive();
+        self.modals.show(ctx);
     }
 }
 
@@ -462,9 +2047,24 @@ impl eframe::App for App {
     fn update(&mut self, ctx: &egui::Context, _: &mut eframe::Frame) {
         self.handle_file_picker_task();
         self.handle_ping_updates();
+        self.handle_recorder();
+        #[cfg(target_os = "linux")]
+        self.handle_daemon_exit();
+        #[cfg(target_os = "linux")]
+        self.handle_tray(ctx);
+        #[cfg(target_os = "linux")]
+        self.handle_close(ctx);
+        self.handle_ping_pause(ctx);
 
         self.render_bottom_bar(ctx);
         self.render_central_panel(ctx);
+        self.render_traceroute(ctx);
+        self.render_auto_select(ctx);
+        self.render_custom_editor(ctx);
+        self.render_profiles(ctx);
+        self.render_log(ctx);
+        #[cfg(target_os = "linux")]
+        self.render_activity(ctx);
         self.render_modal(ctx);
     }
 }
@@ -0,0 +1,132 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::Result;
+use iter_tools::Itertools;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::time::Instant;
+
+use crate::ping::{self, PingStats, PingUpdate};
+use crate::prefixes::{self, Region};
+use crate::recorder::{RecordFormat, Recorder};
+
+#[derive(clap::Args)]
+pub struct MeasureArgs {
+    /// how long to measure for (ex: 30s, 5m)
+    #[arg(long, default_value = "1m", value_parser = humantime::parse_duration)]
+    pub duration: Duration,
+
+    /// delay between ping rounds
+    #[arg(long, default_value = "1s", value_parser = humantime::parse_duration)]
+    pub interval: Duration,
+
+    /// also append every measurement to this file
+    #[arg(long)]
+    pub output: Option<PathBuf>,
+
+    /// format of the output file, guessed from its extension by default
+    #[arg(long, value_enum, requires = "output")]
+    pub format: Option<RecordFormat>,
+}
+
+pub fn measure_main(args: MeasureArgs) -> Result<()> {
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_io()
+        .enable_time()
+        .build()?;
+
+    let regions = prefixes::load();
+    let (mut rx, _control) = ping::setup_pinger(
+        &runtime,
+        regions
+            .iter()
+            .flat_map(|region| {
+                region
+                    .ping_targets()
+                    .map(|target| (region.key.clone(), target))
+            })
+            .sorted()
+            .collect_vec(),
+        args.interval,
+    )?;
+
+    let _recorder = args
+        .output
+        .map(|path| {
+            let format = args
+                .format
+                .unwrap_or_else(|| RecordFormat::from_path(&path));
+            Recorder::start(&runtime, rx.resubscribe(), path, format)
+        })
+        .transpose()?;
+
+    eprintln!(
+        "measuring for {}...",
+        humantime::format_duration(args.duration)
+    );
+
+    let stats = runtime.block_on(async move {
+        let mut stats = HashMap::<(String, IpAddr), PingStats>::new();
+        let mut skipped = 0;
+        let deadline = Instant::now() + args.duration;
+
+        loop {
+            tokio::select! {
+                _ = tokio::time::sleep_until(deadline) => break,
+                result = rx.recv() => match result {
+                    Ok(PingUpdate(key, target, status)) => {
+                        stats.entry((key, target)).or_default().record(&status)
+                    }
+                    Err(RecvError::Lagged(n)) => skipped += n,
+                    Err(RecvError::Closed) => break,
+                },
+            }
+        }
+
+        if skipped > 0 {
+            eprintln!("measuring fell behind, {skipped} ping updates are not counted");
+        }
+
+        stats
+    });
+
+    println!(
+        "{:<24} {:<6} {:<2} {:>5} {:>5} {:>6} {:>8} {:>8} {:>8}",
+        "REGION", "CODE", "IP", "SENT", "LOST", "LOSS", "MIN", "AVG", "MAX"
+    );
+    for region in regions.iter().sorted_by_key(|region| &region.name) {
+        for target in region.ping_targets() {
+            let stats = stats
+                .get(&(region.key.clone(), target))
+                .cloned()
+                .unwrap_or_default();
+            print_stats(region, target, &stats);
+        }
+    }
+
+    Ok(())
+}
+
+fn print_stats(region: &Region, target: IpAddr, stats: &PingStats) {
+    println!(
+        "{:<24} {:<6} {:<2} {:>5} {:>5} {:>5.1}% {:>8} {:>8} {:>8}",
+        region.name,
+        region.code,
+        if target.is_ipv4() { "v4" } else { "v6" },
+        stats.sent,
+        stats.lost(),
+        stats.loss(),
+        format_ms(stats.min),
+        format_ms(stats.avg()),
+        format_ms(stats.max),
+    );
+}
+
+fn format_ms(duration: Option<Duration>) -> String {
+    duration.map_or_else(
+        || "-".to_string(),
+        |d| format!("{:.1} ms", d.as_secs_f64() * 1000.),
+    )
+}
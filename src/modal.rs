@@ -1,4 +1,23 @@
-use eframe::egui::{self, Color32, Image, Modal, ModalResponse, RichText, include_image};
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Local};
+use eframe::egui::{
+    self, Align2, Area, Color32, Frame, Image, Modal, ModalResponse, Order, RichText, Sense, Ui,
+    include_image, vec2,
+};
+use tokio::sync::mpsc;
+
+use crate::i18n::tr;
+
+/// Delay before a toast disappears.
+const TOAST_DURATION: Duration = Duration::from_secs(6);
+
+/// Toasts shown at once, the oldest ones are dismissed first.
+const MAX_TOASTS: usize = 3;
+
+/// Messages kept in the log, the oldest ones are forgotten first.
+const MAX_LOG_ENTRIES: usize = 500;
 
 #[allow(dead_code)]
 #[derive(Debug, Clone)]
@@ -16,6 +35,141 @@ pub struct ModalDisplay {
     pub content: String,
 }
 
+/// Sends messages to the window, waking it up to show them.
+#[derive(Clone)]
+pub struct ModalSender {
+    tx: mpsc::UnboundedSender<ModalDisplay>,
+    ctx: egui::Context,
+}
+
+impl ModalSender {
+    pub fn send(&self, msg: ModalDisplay) {
+        if self.tx.send(msg).is_ok() {
+            self.ctx.request_repaint();
+        }
+    }
+}
+
+/// A message as recorded in the log.
+pub struct LogEntry {
+    pub time: DateTime<Local>,
+    pub msg: ModalDisplay,
+}
+
+struct Toast {
+    shown: Instant,
+    msg: ModalDisplay,
+}
+
+/// Received messages, errors wait for the user to close them while the others are shown as
+/// toasts. All of them are kept in the log.
+pub struct ModalQueue {
+    rx: mpsc::UnboundedReceiver<ModalDisplay>,
+    modals: VecDeque<ModalDisplay>,
+    toasts: VecDeque<Toast>,
+    pub log: VecDeque<LogEntry>,
+
+    /// Whether the log window is shown.
+    pub log_open: bool,
+}
+
+pub fn channel(ctx: &egui::Context) -> (ModalSender, ModalQueue) {
+    let (tx, rx) = mpsc::unbounded_channel();
+
+    let sender = ModalSender {
+        tx,
+        ctx: ctx.clone(),
+    };
+    let queue = ModalQueue {
+        rx,
+        modals: VecDeque::new(),
+        toasts: VecDeque::new(),
+        log: VecDeque::new(),
+        log_open: false,
+    };
+
+    (sender, queue)
+}
+
+impl ModalQueue {
+    /// Moves the messages sent since the last frame to the modals or to the toasts.
+    pub fn receive(&mut self) {
+        while let Ok(msg) = self.rx.try_recv() {
+            if self.log.len() == MAX_LOG_ENTRIES {
+                self.log.pop_front();
+            }
+            self.log.push_back(LogEntry {
+                time: Local::now(),
+                msg: msg.clone(),
+            });
+
+            match msg.level {
+                ModalLevel::Error => self.modals.push_back(msg),
+                _ => {
+                    if self.toasts.len() == MAX_TOASTS {
+                        self.toasts.pop_front();
+                    }
+                    self.toasts.push_back(Toast {
+                        shown: Instant::now(),
+                        msg,
+                    });
+                }
+            }
+        }
+    }
+
+    /// Shows the oldest error in a modal and the toasts which did not expire yet.
+    pub fn show(&mut self, ctx: &egui::Context) {
+        self.toasts
+            .retain(|toast| toast.shown.elapsed() < TOAST_DURATION);
+
+        if let Some(next) = self.toasts.iter().map(|toast| toast.shown).min() {
+            ctx.request_repaint_after(TOAST_DURATION.saturating_sub(next.elapsed()));
+        }
+
+        let mut dismissed = None;
+        if !self.toasts.is_empty() {
+            Area::new("toasts".into())
+                .anchor(Align2::RIGHT_BOTTOM, vec2(-8., -32.))
+                .order(Order::Foreground)
+                .show(ctx, |ui| {
+                    ui.set_max_width(240.);
+
+                    for (i, toast) in self.toasts.iter().enumerate() {
+                        let response = Frame::popup(ui.style())
+                            .show(ui, |ui| {
+                                ui.horizontal(|ui| {
+                                    level_icon(ui, &toast.msg.level);
+                                    ui.strong(&toast.msg.title);
+                                });
+                                if !toast.msg.content.is_empty() {
+                                    ui.label(&toast.msg.content);
+                                }
+                            })
+                            .response
+                            .interact(Sense::click())
+                            .on_hover_text(tr!("toast-dismiss"));
+
+                        if response.clicked() {
+                            dismissed = Some(i);
+                        }
+                    }
+                });
+        }
+        if let Some(i) = dismissed {
+            self.toasts.remove(i);
+        }
+
+        let mut close = false;
+        if let Some(msg) = self.modals.front() {
+            show_modal(ctx, msg, self.modals.len() - 1, || close = true);
+        }
+        if close {
+            self.modals.pop_front();
+        }
+    }
+}
+
 use egui::{Context, FontId, TextStyle};
 
 fn get_font_size(ctx: &Context, text_style: TextStyle) -> f32 {
@@ -27,37 +181,44 @@ fn get_font_size(ctx: &Context, text_style: TextStyle) -> f32 {
     font_id.size
 }
 
+/// Icon and color of a message level, sized like the body text.
+pub fn level_icon(ui: &mut Ui, level: &ModalLevel) {
+    let font_size = get_font_size(ui.ctx(), TextStyle::Body);
+    ui.add(
+        Image::new(match level {
+            ModalLevel::Info => include_image!("../assets/icons/info.svg"),
+            ModalLevel::Warning => include_image!("../assets/icons/triangle-alert.svg"),
+            ModalLevel::Error => include_image!("../assets/icons/circle-x.svg"),
+            ModalLevel::Success => include_image!("../assets/icons/check.svg"),
+        })
+        .tint(match level {
+            ModalLevel::Info => match ui.visuals().dark_mode {
+                true => Color32::from_rgb(100, 100, 255),
+                false => Color32::from_rgb(0, 75, 255),
+            },
+            ModalLevel::Warning => ui.visuals().warn_fg_color,
+            ModalLevel::Error => ui.visuals().error_fg_color,
+            ModalLevel::Success => match ui.visuals().dark_mode {
+                true => Color32::from_rgb(72, 240, 72),
+                false => Color32::from_rgb(0, 132, 21),
+            },
+        })
+        .maintain_aspect_ratio(true)
+        .max_height(font_size)
+        .fit_to_fraction([1.0, 1.0].into()),
+    );
+}
+
+/// Shows a message until closed, `pending` is the amount of messages waiting after it.
 pub fn show_modal(
     ctx: &egui::Context,
     msg: &ModalDisplay,
+    pending: usize,
     mut on_close: impl FnMut(),
 ) -> ModalResponse<()> {
     let res = Modal::new(format!("modal {}", msg.title).into()).show(ctx, |ui| {
         ui.horizontal(|ui| {
-            let font_size = get_font_size(ctx, TextStyle::Body);
-            ui.add(
-                Image::new(match msg.level {
-                    ModalLevel::Info => include_image!("../assets/icons/info.svg"),
-                    ModalLevel::Warning => include_image!("../assets/icons/triangle-alert.svg"),
-                    ModalLevel::Error => include_image!("../assets/icons/circle-x.svg"),
-                    ModalLevel::Success => include_image!("../assets/icons/check.svg"),
-                })
-                .tint(match msg.level {
-                    ModalLevel::Info => match ui.visuals().dark_mode {
-                        true => Color32::from_rgb(100, 100, 255),
-                        false => Color32::from_rgb(0, 75, 255),
-                    },
-                    ModalLevel::Warning => ui.visuals().warn_fg_color,
-                    ModalLevel::Error => ui.visuals().error_fg_color,
-                    ModalLevel::Success => match ui.visuals().dark_mode {
-                        true => Color32::from_rgb(72, 240, 72),
-                        false => Color32::from_rgb(0, 132, 21),
-                    },
-                })
-                .maintain_aspect_ratio(true)
-                .max_height(font_size)
-                .fit_to_fraction([1.0, 1.0].into()),
-            );
+            level_icon(ui, &msg.level);
             ui.label(RichText::new(&msg.title));
         });
         ui.separator();
@@ -67,7 +228,11 @@ pub fn show_modal(
         ui.with_layout(
             egui::Layout::top_down_justified(egui::Align::Center),
             |ui| {
-                if ui.button("close").clicked() {
+                let label = match pending {
+                    0 => tr!("modal-close"),
+                    _ => tr!("modal-next", pending = pending),
+                };
+                if ui.button(label).clicked() {
                     on_close();
                 }
             },
@@ -0,0 +1,151 @@
+//! Desktop notifications about the daemon, sent from the user's session since it runs as root.
+
+use std::collections::BTreeSet;
+use std::process::Child;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+use iter_tools::Itertools;
+use notify_rust::{Notification, Urgency};
+use tokio::runtime::Runtime;
+use tokio::task::{self, JoinHandle};
+use tokio::time;
+
+use crate::daemon;
+use crate::i18n::tr;
+use crate::modal::{ModalDisplay, ModalLevel, ModalSender};
+
+/// Delay between two requests to the daemon.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Watches a started daemon until dropped, notifying when it starts blocking, when it moves game
+/// processes and when it exits on its own.
+pub struct DaemonMonitor {
+    /// Set once the daemon exited, to whether its rules are gone.
+    exited: Arc<OnceLock<bool>>,
+    handle: JoinHandle<()>,
+}
+
+impl DaemonMonitor {
+    pub fn start(runtime: &Runtime, mut daemon: Child, modal_tx: ModalSender) -> Self {
+        let exited = Arc::new(OnceLock::new());
+
+        let handle = runtime.spawn({
+            let exited = exited.clone();
+
+            async move {
+                let mut exit = task::spawn_blocking(move || daemon.wait());
+                let mut poll = time::interval(POLL_INTERVAL);
+                let mut active = false;
+                let mut moved = BTreeSet::new();
+
+                loop {
+                    tokio::select! {
+                        status = &mut exit => {
+                            // the daemon removes its rules unless it crashed or was killed
+                            let (status, removed) = match status {
+                                Ok(Ok(status)) => (status.to_string(), status.success() || !active),
+                                Ok(Err(e)) => (e.to_string(), !active),
+                                Err(e) => (e.to_string(), !active),
+                            };
+
+                            exited.set(removed).ok();
+                            notify_exit(active, removed, status, &modal_tx).await;
+                            return;
+                        }
+                        _ = poll.tick() => poll_daemon(&mut active, &mut moved, &modal_tx).await,
+                    }
+                }
+            }
+        });
+
+        Self { exited, handle }
+    }
+
+    /// Whether the rules of the daemon are gone, once it exited without being stopped.
+    pub fn exited(&self) -> Option<bool> {
+        self.exited.get().copied()
+    }
+}
+
+impl Drop for DaemonMonitor {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+/// Notifies when the daemon answers for the first time and when it moved new game processes.
+async fn poll_daemon(active: &mut bool, moved: &mut BTreeSet<i32>, modal_tx: &ModalSender) {
+    // the daemon answers once it is blocking
+    let Ok(Ok(pids)) = task::spawn_blocking(daemon::processes).await else {
+        return;
+    };
+
+    if !*active {
+        *active = true;
+        modal_tx.send(ModalDisplay {
+            level: ModalLevel::Success,
+            title: tr!("enabled-title"),
+            content: tr!("enabled-content"),
+        });
+        notify(
+            &tr!("enabled-title"),
+            &tr!("enabled-content"),
+            Urgency::Normal,
+        )
+        .await;
+    }
+
+    let new = pids
+        .into_iter()
+        .filter(|pid| moved.insert(*pid))
+        .collect::<Vec<_>>();
+    if !new.is_empty() {
+        let pids = new.iter().join(", ");
+        let content = tr!("game-moved-content", count = new.len(), pids = pids);
+        notify(&tr!("game-moved-title"), &content, Urgency::Low).await;
+    }
+}
+
+/// Reports the exit of the daemon, `active` tells whether it was blocking before and `removed`
+/// whether its rules are gone.
+async fn notify_exit(active: bool, removed: bool, status: String, modal_tx: &ModalSender) {
+    let (title, content) = if active && removed {
+        (
+            tr!("daemon-exited-title"),
+            tr!("daemon-exited-content", status = status),
+        )
+    } else if active {
+        (
+            tr!("daemon-exited-title"),
+            tr!("daemon-crashed-content", status = status),
+        )
+    } else {
+        (
+            tr!("enable-failed-title"),
+            tr!("daemon-failed-content", status = status),
+        )
+    };
+
+    notify(&title, &content, Urgency::Critical).await;
+    modal_tx.send(ModalDisplay {
+        level: ModalLevel::Error,
+        title,
+        content,
+    });
+}
+
+async fn notify(summary: &str, body: &str, urgency: Urgency) {
+    let result = Notification::new()
+        .appname(&tr!("app-title"))
+        .summary(summary)
+        .body(body)
+        .icon("security-high")
+        .urgency(urgency)
+        .show_async()
+        .await;
+
+    if let Err(e) = result {
+        eprintln!("failed to send a notification: {e}");
+    }
+}
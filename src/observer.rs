@@ -0,0 +1,77 @@
+use std::time::{Duration, Instant};
+
+use crate::daemon;
+use crate::fw::{ObservedConnection, RuleCounter};
+use crate::i18n::tr;
+
+/// Delay between two requests to the daemon while the window is shown.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Dropped traffic and game connections reported by the daemon.
+#[derive(Default)]
+pub struct DaemonActivity {
+    /// Whether the activity window is shown.
+    pub open: bool,
+
+    /// Counters of the blocking rules.
+    pub counters: Vec<RuleCounter>,
+
+    /// Connections of the last answer, most recently seen first.
+    pub connections: Vec<ObservedConnection>,
+
+    /// Why the last request failed.
+    pub error: Option<String>,
+
+    last_poll: Option<Instant>,
+}
+
+impl DaemonActivity {
+    /// Asks the daemon for its counters and connections when the last answers are outdated.
+    ///
+    /// Returns the delay until the next request.
+    pub fn poll(&mut self) -> Duration {
+        if let Some(last) = self.last_poll
+            && last.elapsed() < POLL_INTERVAL
+        {
+            return POLL_INTERVAL - last.elapsed();
+        }
+
+        self.last_poll = Some(Instant::now());
+
+        let result =
+            daemon::rule_counters().and_then(|counters| Ok((counters, daemon::connections()?)));
+
+        match result {
+            Ok((counters, mut connections)) => {
+                connections.sort_by(|a, b| b.last_seen.cmp(&a.last_seen));
+                self.counters = counters;
+                self.connections = connections;
+                self.error = None;
+            }
+            Err(daemon::KillError::Refused) => {
+                self.error = Some(tr!("activity-disabled"));
+            }
+            Err(e) => self.error = Some(e.to_string()),
+        }
+
+        POLL_INTERVAL
+    }
+}
+
+/// Formats a byte count with a binary unit (ex: `1.5 KiB`).
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024. && unit < UNITS.len() - 1 {
+        value /= 1024.;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
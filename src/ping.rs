@@ -3,11 +3,14 @@ use std::net::IpAddr;
 use std::time::Duration;
 
 use anyhow::{Result, anyhow};
-use surge_ping::{self, Client, Config};
+use surge_ping::{self, Client, Config, ICMP, Pinger};
 use tokio::runtime::Runtime;
-use tokio::sync::broadcast;
+use tokio::sync::{broadcast, watch};
 use tokio::time;
 
+/// Delay between ping rounds in the GUI.
+pub const DEFAULT_INTERVAL: Duration = Duration::from_secs(10);
+
 #[derive(Clone, Debug)]
 pub enum PingStatus {
     /// The endpoint has not been pinged yet.
@@ -28,18 +31,121 @@ impl PingStatus {
     }
 }
 
+/// Aggregated statistics of the ping responses of a single endpoint.
+#[derive(Clone, Debug, Default)]
+pub struct PingStats {
+    pub sent: u32,
+    pub received: u32,
+    pub min: Option<Duration>,
+    pub max: Option<Duration>,
+    pub total: Duration,
+}
+
+impl PingStats {
+    pub fn record(&mut self, status: &PingStatus) {
+        match status {
+            PingStatus::Unknown => return,
+            PingStatus::Reachable(duration) => {
+                self.received += 1;
+                self.total += *duration;
+                self.min = Some(self.min.map_or(*duration, |min| min.min(*duration)));
+                self.max = Some(self.max.map_or(*duration, |max| max.max(*duration)));
+            }
+            PingStatus::Unreachable => {}
+        }
+
+        self.sent += 1;
+    }
+
+    pub fn lost(&self) -> u32 {
+        self.sent - self.received
+    }
+
+    /// Percentage of pings which were not answered.
+    pub fn loss(&self) -> f64 {
+        if self.sent == 0 {
+            0.
+        } else {
+            f64::from(self.lost()) * 100. / f64::from(self.sent)
+        }
+    }
+
+    pub fn avg(&self) -> Option<Duration> {
+        (self.received > 0).then(|| self.total / self.received)
+    }
+}
+
+/// Result of pinging a region's target address.
 #[derive(Clone, Debug)]
-pub struct PingUpdate(pub String, pub PingStatus);
+pub struct PingUpdate(pub String, pub IpAddr, pub PingStatus);
 
 pub type PingReceiver = broadcast::Receiver<PingUpdate>;
 
-pub fn setup_pinger(runtime: &Runtime, endpoints: Vec<(String, IpAddr)>) -> Result<PingReceiver> {
-    let (tx, rx) = broadcast::channel(endpoints.len());
+/// Rounds of updates kept for slow receivers, older ones are dropped once it is full.
+const BUFFERED_ROUNDS: usize = 16;
+
+/// Consecutive failures after which an endpoint starts being probed less often.
+const BACKOFF_THRESHOLD: u32 = 3;
+
+/// Highest amount of rounds skipped between probes of an unreachable endpoint.
+const MAX_BACKOFF_ROUNDS: u32 = 16;
+
+/// Allows pausing a running pinger, e.g. while nobody is looking at the results.
+pub struct PingControl {
+    paused: watch::Sender<bool>,
+}
+
+impl PingControl {
+    pub fn set_paused(&self, paused: bool) {
+        self.paused.send_if_modified(|current| {
+            let changed = *current != paused;
+            *current = paused;
+            changed
+        });
+    }
+}
+
+struct Endpoint {
+    pinger: Pinger,
+    /// Consecutive unanswered pings.
+    failures: u32,
+    /// Rounds left to skip before the next ping.
+    skip: u32,
+}
+
+impl Endpoint {
+    fn update_backoff(&mut self, status: &PingStatus) {
+        if let PingStatus::Reachable(_) = status {
+            self.failures = 0;
+            return;
+        }
+
+        self.failures += 1;
+        if self.failures >= BACKOFF_THRESHOLD {
+            let exp = (self.failures - BACKOFF_THRESHOLD).min(MAX_BACKOFF_ROUNDS.ilog2());
+            self.skip = 1 << exp;
+        }
+    }
+}
+
+pub fn setup_pinger(
+    runtime: &Runtime,
+    endpoints: Vec<(String, IpAddr)>,
+    interval: Duration,
+) -> Result<(PingReceiver, PingControl)> {
+    let (tx, rx) = broadcast::channel(endpoints.len().max(1) * BUFFERED_ROUNDS);
+    let (paused_tx, mut paused) = watch::channel(false);
 
     let client = runtime
         .block_on(async move { Client::new(&Config::default()) })
         .map_err(|err| anyhow!("unable to initialize ping client: {}", err))?;
 
+    // hosts without IPv6 support still get to ping IPv4 endpoints
+    let client6 = runtime
+        .block_on(async move { Client::new(&Config::builder().kind(ICMP::V6).build()) })
+        .inspect_err(|err| eprintln!("unable to initialize ICMPv6 ping client: {err}"))
+        .ok();
+
     runtime.spawn(async move {
         let mut pingers = HashMap::new();
 
@@ -47,19 +153,41 @@ pub fn setup_pinger(runtime: &Runtime, endpoints: Vec<(String, IpAddr)>) -> Resu
             let ident = std::process::id() as u16;
 
             for (key, host) in endpoints {
-                pingers.insert(key, client.pinger(host, ident.into()).await);
+                let client = match host {
+                    IpAddr::V4(_) => &client,
+                    IpAddr::V6(_) => match &client6 {
+                        Some(client6) => client6,
+                        None => continue,
+                    },
+                };
+                let endpoint = Endpoint {
+                    pinger: client.pinger(host, ident.into()).await,
+                    failures: 0,
+                    skip: 0,
+                };
+                pingers.insert((key, host), endpoint);
             }
         }
 
         let mut seq = 0u16;
 
         loop {
-            for (key, pinger) in pingers.iter_mut() {
+            if *paused.borrow_and_update() {
+                let _ = paused.wait_for(|paused| !paused).await;
+            }
+
+            for ((key, host), endpoint) in pingers.iter_mut() {
+                if endpoint.skip > 0 {
+                    endpoint.skip -= 1;
+                    continue;
+                }
+
                 let tx_clone = tx.clone();
                 seq = seq.wrapping_add(1);
 
                 async move {
-                    let response = pinger
+                    let response = endpoint
+                        .pinger
                         .ping(seq.into(), b"github.com/trash-pandy/ow2-server-picker")
                         .await;
 
@@ -67,17 +195,22 @@ pub fn setup_pinger(runtime: &Runtime, endpoints: Vec<(String, IpAddr)>) -> Resu
                         Ok((_, duration)) => PingStatus::Reachable(duration),
                         Err(_) => PingStatus::Unreachable,
                     };
+                    endpoint.update_backoff(&status);
 
                     tx_clone
-                        .send(PingUpdate(key.clone(), status))
+                        .send(PingUpdate(key.clone(), *host, status))
                         .expect("failed to broadcast a ping update");
                 }
                 .await;
             }
 
-            time::sleep(Duration::from_secs(10)).await;
+            // wake up early on pause changes so that resuming pings right away
+            tokio::select! {
+                _ = time::sleep(interval) => {}
+                Ok(()) = paused.changed() => {}
+            }
         }
     });
 
-    Ok(rx)
+    Ok((rx, PingControl { paused: paused_tx }))
 }
@@ -6,6 +6,12 @@ use std::sync::LazyLock;
 
 use ipnetwork::IpNetwork;
 use iter_tools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+use crate::i18n::tr;
+use crate::validate::{self, Issue};
+use crate::{cidr, regiondb};
 
 ///// sourced from https://github.com/stowmyy/dropship/blob/main/dropship/dropship/src/core/Settings.h#L64
 /// find: static const std::string (.*?) \{ (".*?") \};
@@ -128,7 +134,146 @@ static META: LazyLock<HashMap<&str, (&str, &str, &str)>> = LazyLock::new(|| {
     .collect()
 });
 
+/// IPv6 ping targets, these are not part of the upstream metadata.
+///
+/// Google regions are pinged at the first host of their IPv6 prefix.
+static META_V6: LazyLock<HashMap<&str, &str>> = LazyLock::new(|| {
+    [
+        ("google/europe-north1", "2600:1900:4150::1"),
+        ("google/asia-southeast1", "2600:1900:4080::1"),
+        ("google/southamerica-east1", "2600:1900:40f0::1"),
+        ("google/asia-northeast1", "2600:1900:4050::1"),
+        ("google/me-central2", "2600:1900:5400::1"),
+    ]
+    .iter()
+    .copied()
+    .collect()
+});
+
+/// Continents of the bundled regions, these are not part of the upstream metadata.
+static CONTINENTS: LazyLock<HashMap<&str, Continent>> = LazyLock::new(|| {
+    [
+        ("blizzard/ord1", Continent::NorthAmerica),
+        ("blizzard/las1", Continent::NorthAmerica),
+        ("google/europe-north1", Continent::Europe),
+        ("google/asia-southeast1", Continent::Asia),
+        ("google/southamerica-east1", Continent::SouthAmerica),
+        ("google/asia-northeast1", Continent::Asia),
+        ("google/me-central2", Continent::MiddleEast),
+        ("blizzard/icn1", Continent::Asia),
+        ("blizzard/syd2", Continent::Oceania),
+        ("blizzard/tpe1", Continent::Asia),
+        ("blizzard/ams1", Continent::Europe),
+    ]
+    .iter()
+    .copied()
+    .collect()
+});
+
+/// Regions with the customizations of the configuration applied.
 pub fn load() -> Vec<Region> {
+    let (regions, warnings) = load_with_warnings();
+    for warning in warnings {
+        eprintln!("warning: {warning}");
+    }
+
+    regions
+}
+
+/// Same as [`load`], returning the problems found in the region list instead of printing them.
+pub fn load_with_warnings() -> (Vec<Region>, Vec<String>) {
+    let (mut regions, mut warnings) = published_with_warnings();
+
+    match Config::load() {
+        Ok(config) => warnings.extend(customize(&mut regions, &config)),
+        Err(e) => warnings.push(tr!("issue-custom-ignored", error = format!("{e:#}"))),
+    }
+
+    warnings.extend(validate::validate(&regions).iter().map(Issue::to_string));
+
+    (regions, warnings)
+}
+
+/// Applies the prefix overrides and adds the custom regions of the configuration.
+///
+/// Returns warnings about customizations that were skipped.
+pub fn customize(regions: &mut Vec<Region>, config: &Config) -> Vec<String> {
+    let mut warnings = Vec::new();
+
+    for (key, prefix_override) in &config.prefix_overrides {
+        let Some(region) = regions.iter_mut().find(|r| &r.key == key) else {
+            warnings.push(tr!("issue-override-unknown", key = key.as_str()));
+            continue;
+        };
+
+        for excluded in &prefix_override.excluded {
+            if !region.prefixes.iter().any(|p| cidr::overlaps(excluded, p)) {
+                warnings.push(tr!(
+                    "issue-excluded-unused",
+                    prefix = excluded.to_string(),
+                    key = key.as_str(),
+                ));
+                continue;
+            }
+
+            // only the prefixes sharing addresses are split, the others keep their order
+            region.prefixes = region
+                .prefixes
+                .iter()
+                .flat_map(|prefix| {
+                    if cidr::overlaps(excluded, prefix) {
+                        cidr::subtract([*prefix], [*excluded])
+                    } else {
+                        vec![*prefix]
+                    }
+                })
+                .collect();
+        }
+
+        for extra in &prefix_override.extra {
+            if !region.prefixes.contains(extra) {
+                region.prefixes.push(*extra);
+            }
+        }
+    }
+
+    for custom in &config.custom_regions {
+        if regions.iter().any(|r| r.key == custom.key) {
+            warnings.push(tr!("issue-custom-exists", key = custom.key.as_str()));
+            continue;
+        }
+        regions.push(custom.clone());
+    }
+
+    warnings
+}
+
+/// Regions of the cached region database, or the bundled ones if there is none.
+pub fn published() -> Vec<Region> {
+    let (regions, warnings) = published_with_warnings();
+    for warning in warnings {
+        eprintln!("warning: {warning}");
+    }
+
+    regions
+}
+
+fn published_with_warnings() -> (Vec<Region>, Vec<String>) {
+    match regiondb::load_cached() {
+        Ok(Some(db)) => (db.regions, Vec::new()),
+        Ok(None) => (bundled(), invalid_bundled()),
+        Err(e) => {
+            let mut warnings = vec![tr!("issue-cache-ignored", error = format!("{e:#}"))];
+            warnings.extend(invalid_bundled());
+            (bundled(), warnings)
+        }
+    }
+}
+
+/// Regions shipped with the application.
+///
+/// Prefixes that fail to parse are left out, they are reported by [`invalid_bundled`].
+pub fn bundled() -> Vec<Region> {
     let mut blocks = Vec::with_capacity(PREFIXES.len());
     for &key in PREFIXES.keys() {
         let &prefix = PREFIXES.get(key).unwrap();
@@ -139,21 +284,109 @@ pub fn load() -> Vec<Region> {
             name: region.to_string(),
             code: code.to_string(),
             ping: addr.parse().unwrap(),
-            prefixes: prefix.split(",").map(|v| v.parse().unwrap()).collect_vec(),
+            ping6: META_V6.get(key).map(|addr| addr.parse().unwrap()),
+            continent: CONTINENTS.get(key).copied().unwrap_or_default(),
+            prefixes: prefix
+                .split(",")
+                .filter_map(|v| v.parse().ok())
+                .collect_vec(),
         });
     }
     blocks
 }
 
-#[derive(Clone, PartialEq, PartialOrd, Eq, Ord)]
+/// Prefixes of the bundled regions that fail to parse.
+pub fn invalid_bundled() -> Vec<String> {
+    PREFIXES
+        .iter()
+        .sorted()
+        .flat_map(|(&key, &prefixes)| {
+            prefixes.split(",").filter_map(move |value| {
+                let error = value.parse::<IpNetwork>().err()?;
+                Some(
+                    Issue::Invalid {
+                        key: key.to_string(),
+                        value: value.to_string(),
+                        error: error.to_string(),
+                    }
+                    .to_string(),
+                )
+            })
+        })
+        .collect()
+}
+
+#[derive(Clone, PartialEq, PartialOrd, Eq, Ord, Serialize, Deserialize)]
 pub struct Region {
     pub key: String,
     pub name: String,
     pub code: String,
     pub ping: IpAddr,
+    #[serde(default)]
+    pub ping6: Option<IpAddr>,
+    #[serde(default)]
+    pub continent: Continent,
     pub prefixes: Vec<IpNetwork>,
 }
 
+#[derive(Clone, Copy, Default, PartialEq, PartialOrd, Eq, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Continent {
+    NorthAmerica,
+    SouthAmerica,
+    Europe,
+    MiddleEast,
+    Asia,
+    Oceania,
+    #[default]
+    Unknown,
+}
+
+impl Continent {
+    pub const ALL: [Continent; 7] = [
+        Continent::NorthAmerica,
+        Continent::SouthAmerica,
+        Continent::Europe,
+        Continent::MiddleEast,
+        Continent::Asia,
+        Continent::Oceania,
+        Continent::Unknown,
+    ];
+}
+
+impl Display for Continent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Continent::NorthAmerica => tr!("continent-north-america"),
+            Continent::SouthAmerica => tr!("continent-south-america"),
+            Continent::Europe => tr!("continent-europe"),
+            Continent::MiddleEast => tr!("continent-middle-east"),
+            Continent::Asia => tr!("continent-asia"),
+            Continent::Oceania => tr!("continent-oceania"),
+            Continent::Unknown => tr!("continent-unknown"),
+        };
+        f.write_str(&name)
+    }
+}
+
+impl Region {
+    /// Name of the provider hosting the region, taken from the first part of its key.
+    pub fn provider(&self) -> String {
+        match self.key.split_once('/') {
+            Some(("blizzard", _)) => tr!("provider-blizzard"),
+            Some(("google", _)) => tr!("provider-google"),
+            Some(("custom", _)) => tr!("provider-custom"),
+            Some((provider, _)) => provider.to_string(),
+            None => tr!("provider-other"),
+        }
+    }
+
+    /// Addresses to ping for this region, IPv4 first.
+    pub fn ping_targets(&self) -> impl Iterator<Item = IpAddr> {
+        std::iter::once(self.ping).chain(self.ping6)
+    }
+}
+
 impl Hash for Region {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.key.hash(state);
@@ -165,3 +398,67 @@ impl Display for Region {
         f.write_str(&self.key)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::PrefixOverride;
+
+    fn region(key: &str, prefixes: &[&str]) -> Region {
+        Region {
+            key: key.into(),
+            name: key.into(),
+            code: key.into(),
+            ping: "192.0.2.1".parse().unwrap(),
+            ping6: None,
+            continent: Continent::Unknown,
+            prefixes: prefixes.iter().map(|p| p.parse().unwrap()).collect(),
+        }
+    }
+
+    fn exclude(key: &str, excluded: &[&str]) -> Config {
+        let prefix_override = PrefixOverride {
+            extra: Vec::new(),
+            excluded: excluded.iter().map(|p| p.parse().unwrap()).collect(),
+        };
+
+        Config {
+            prefix_overrides: [(key.to_string(), prefix_override)].into(),
+            ..Default::default()
+        }
+    }
+
+    fn prefixes(region: &Region) -> Vec<String> {
+        region.prefixes.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn excluded_range_is_carved_out() {
+        let mut regions = vec![region("a", &["10.1.0.0/24", "10.0.0.0/16"])];
+
+        let warnings = customize(&mut regions, &exclude("a", &["10.0.128.0/17"]));
+
+        assert!(warnings.is_empty());
+        assert_eq!(prefixes(&regions[0]), ["10.1.0.0/24", "10.0.0.0/17"]);
+    }
+
+    #[test]
+    fn excluded_range_removes_contained_prefixes() {
+        let mut regions = vec![region("a", &["10.0.0.0/24", "10.0.1.0/24"])];
+
+        let warnings = customize(&mut regions, &exclude("a", &["10.0.0.0/16"]));
+
+        assert!(warnings.is_empty());
+        assert!(regions[0].prefixes.is_empty());
+    }
+
+    #[test]
+    fn excluded_range_without_overlap_warns() {
+        let mut regions = vec![region("a", &["10.0.0.0/24"])];
+
+        let warnings = customize(&mut regions, &exclude("a", &["10.1.0.0/24"]));
+
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(prefixes(&regions[0]), ["10.0.0.0/24"]);
+    }
+}
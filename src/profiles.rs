@@ -0,0 +1,111 @@
+use anyhow::{Result, anyhow, bail, ensure};
+
+use crate::config::Config;
+use crate::daemon::{self, KillError};
+use crate::i18n::tr;
+use crate::{blocking, prefixes};
+
+#[derive(clap::Args)]
+pub struct ApplyArgs {
+    /// name of the saved profile to apply
+    #[arg(long)]
+    profile: String,
+
+    /// path of the game executable
+    #[arg(long)]
+    game_path: String,
+}
+
+pub fn apply_main(args: ApplyArgs) -> Result<()> {
+    let mut config = Config::load()?;
+    let profile = config.profiles.get(&args.profile).ok_or_else(|| {
+        let names = config
+            .profiles
+            .keys()
+            .map(String::as_str)
+            .collect::<Vec<_>>();
+        if names.is_empty() {
+            anyhow!("no profile named {:?}, none are saved yet", args.profile)
+        } else {
+            anyhow!(
+                "no profile named {:?}, saved profiles: {}",
+                args.profile,
+                names.join(", ")
+            )
+        }
+    })?;
+
+    let regions = prefixes::load();
+    for key in &profile.regions {
+        if !regions.iter().any(|region| &region.key == key) {
+            eprintln!("warning: region {key} of the profile is not in the region list");
+        }
+    }
+    ensure!(
+        regions
+            .iter()
+            .any(|region| profile.regions.contains(&region.key)),
+        "the profile selects no known region"
+    );
+
+    let blocked = blocking::blocked_prefixes(
+        profile.block_mode,
+        regions
+            .iter()
+            .map(|region| (region, profile.regions.contains(&region.key))),
+        &config.allowlist,
+    );
+
+    match daemon::kill() {
+        Ok(()) | Err(KillError::Refused) => {}
+        Err(e) => bail!("failed to stop the running blocking: {e}"),
+    }
+    #[cfg_attr(not(target_os = "linux"), allow(unused_variables, unused_mut))]
+    let mut started = daemon::start(blocked, args.game_path)?;
+    #[cfg(target_os = "linux")]
+    daemon::wait_until_running(&mut started)?;
+
+    config.active_profile = Some(args.profile);
+    config.save()?;
+
+    Ok(())
+}
+
+/// Change requested in the profile management window.
+pub enum ProfileAction {
+    /// Create a profile named after [`ProfileManager::new_name`] from the current selection.
+    Create,
+    /// Replace the regions and mode of a profile with the current ones.
+    Save(String),
+    /// Rename a profile, from the first name to the second one.
+    Rename(String, String),
+    Delete(String),
+}
+
+/// State of the profile management window.
+#[derive(Default)]
+pub struct ProfileManager {
+    /// Whether the window is shown.
+    pub open: bool,
+
+    /// Name of the profile to create.
+    pub new_name: String,
+
+    /// Profile being renamed and its new name.
+    pub renaming: Option<(String, String)>,
+
+    /// Why the last change was refused.
+    pub error: Option<String>,
+}
+
+impl ProfileManager {
+    /// Checks that `name` can be used for a new profile.
+    pub fn check_name(config: &Config, name: &str) -> Result<()> {
+        ensure!(!name.trim().is_empty(), tr!("profile-name-missing"));
+        ensure!(
+            !config.profiles.contains_key(name.trim()),
+            tr!("profile-name-taken", name = name.trim())
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,133 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use tokio::fs::{File, OpenOptions};
+use tokio::io::AsyncWriteExt;
+use tokio::runtime::Runtime;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::task::JoinHandle;
+
+use crate::ping::{PingReceiver, PingStatus, PingUpdate};
+
+const CSV_HEADER: &str = "timestamp,region,target,latency_ms,lost\n";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum RecordFormat {
+    /// Comma separated values with a header row.
+    Csv,
+    /// One JSON object per line.
+    Jsonl,
+}
+
+impl RecordFormat {
+    /// Picks the format based on the file extension, defaulting to JSON lines.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension() {
+            Some(ext) if ext.eq_ignore_ascii_case("csv") => RecordFormat::Csv,
+            _ => RecordFormat::Jsonl,
+        }
+    }
+
+    fn format(&self, update: &PingUpdate) -> Option<String> {
+        let PingUpdate(key, target, status) = update;
+        let timestamp = chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
+        let latency = match status {
+            PingStatus::Unknown => return None,
+            PingStatus::Reachable(duration) => Some(duration.as_secs_f64() * 1000.),
+            PingStatus::Unreachable => None,
+        };
+
+        Some(match self {
+            RecordFormat::Csv => format!(
+                "{timestamp},{key},{target},{},{}\n",
+                latency.map(|v| format!("{v:.3}")).unwrap_or_default(),
+                latency.is_none(),
+            ),
+            RecordFormat::Jsonl => {
+                let mut line = serde_json::json!({
+                    "timestamp": timestamp,
+                    "region": key,
+                    "target": target,
+                    "latency_ms": latency,
+                    "lost": latency.is_none(),
+                })
+                .to_string();
+                line.push('\n');
+                line
+            }
+        })
+    }
+}
+
+/// Appends every ping update to a file until dropped.
+pub struct Recorder {
+    pub path: PathBuf,
+    handle: JoinHandle<Result<()>>,
+}
+
+impl Recorder {
+    pub fn start(
+        runtime: &Runtime,
+        mut rx: PingReceiver,
+        path: PathBuf,
+        format: RecordFormat,
+    ) -> Result<Self> {
+        let mut file = runtime.block_on(open(&path, format))?;
+
+        let handle = runtime.spawn(async move {
+            loop {
+                let update = match rx.recv().await {
+                    Ok(update) => update,
+                    Err(RecvError::Lagged(skipped)) => {
+                        eprintln!("recording fell behind, {skipped} ping updates are missing");
+                        continue;
+                    }
+                    Err(RecvError::Closed) => break,
+                };
+
+                if let Some(line) = format.format(&update) {
+                    file.write_all(line.as_bytes()).await?;
+                    file.flush().await?;
+                }
+            }
+
+            Ok(())
+        });
+
+        Ok(Self { path, handle })
+    }
+
+    /// Returns the outcome of the recording once it has stopped on its own.
+    pub fn poll(&mut self, runtime: &Runtime) -> Option<Result<()>> {
+        if !self.handle.is_finished() {
+            return None;
+        }
+
+        Some(
+            runtime
+                .block_on(&mut self.handle)
+                .map_err(Into::into)
+                .flatten(),
+        )
+    }
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+async fn open(path: &Path, format: RecordFormat) -> Result<File> {
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .await?;
+
+    if format == RecordFormat::Csv && file.metadata().await?.len() == 0 {
+        file.write_all(CSV_HEADER.as_bytes()).await?;
+    }
+
+    Ok(file)
+}
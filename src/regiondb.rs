@@ -0,0 +1,356 @@
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{Context, Result, anyhow, ensure};
+use ed25519_dalek::pkcs8::DecodePrivateKey;
+use ed25519_dalek::pkcs8::spki::DecodePublicKey;
+use ed25519_dalek::{Signature, Signer, SigningKey, VerifyingKey};
+use serde::{Deserialize, Serialize};
+
+use crate::config::{APP_DIR, Config};
+use crate::prefixes::{self, Region};
+
+/// Key the published region databases are signed with.
+const PUBLIC_KEY: &str = include_str!("../assets/keys/regiondb.pub.pem");
+
+/// Extension appended to the database location to get its detached signature.
+const SIGNATURE_EXT: &str = "sig";
+
+/// Longest a download may take, from connecting to reading the whole body.
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Serialize, Deserialize)]
+pub struct RegionDb {
+    /// Increases with every published database, older ones are never applied.
+    pub serial: u64,
+    pub regions: Vec<Region>,
+}
+
+#[derive(clap::Subcommand)]
+pub enum DbCommand {
+    /// download the region database and cache it if its signature is valid
+    Update {
+        /// location of the database, defaults to `region_db_url` of the config
+        #[arg(long)]
+        url: Option<String>,
+    },
+    /// write the current region database to a file
+    Export {
+        /// serial number of the exported database
+        #[arg(long, default_value_t = 0)]
+        serial: u64,
+
+        /// export the set shipped with the application instead of the active one
+        #[arg(long)]
+        bundled: bool,
+
+        output: PathBuf,
+    },
+    /// sign a region database, writing the signature next to it
+    Sign {
+        /// ed25519 private key in PKCS#8 PEM format (ex: `openssl genpkey -algorithm ed25519`)
+        #[arg(long)]
+        key: PathBuf,
+
+        file: PathBuf,
+    },
+}
+
+pub fn db_main(command: DbCommand) -> Result<()> {
+    match command {
+        DbCommand::Update { url } => {
+            let url = match url {
+                Some(url) => url,
+                None => Config::load()?
+                    .region_db_url
+                    .ok_or_else(|| anyhow!("no region_db_url configured"))?,
+            };
+
+            match update(&url)? {
+                Some(db) => println!(
+                    "updated to serial {} with {} regions",
+                    db.serial,
+                    db.regions.len()
+                ),
+                None => println!("already up to date"),
+            }
+        }
+        DbCommand::Export {
+            serial,
+            bundled,
+            output,
+        } => {
+            let regions = if bundled {
+                prefixes::bundled()
+            } else {
+                prefixes::published()
+            };
+            fs::write(
+                output,
+                serde_json::to_vec_pretty(&RegionDb { serial, regions })?,
+            )?;
+        }
+        DbCommand::Sign { key, file } => {
+            let key = SigningKey::read_pkcs8_pem_file(&key)
+                .map_err(|e| anyhow!("failed to read {}: {e}", key.display()))?;
+            let data = fs::read(&file)?;
+
+            // refuse to sign something the application would not load
+            parse(&data)?;
+
+            fs::write(signature_path(&file), key.sign(&data).to_bytes())?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Location of the last verified database downloaded.
+pub fn cache_path() -> Option<PathBuf> {
+    Some(dirs::cache_dir()?.join(APP_DIR).join("regions.json"))
+}
+
+fn signature_path(path: &Path) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(".");
+    path.push(SIGNATURE_EXT);
+    path.into()
+}
+
+fn public_key() -> VerifyingKey {
+    VerifyingKey::from_public_key_pem(PUBLIC_KEY).expect("invalid embedded public key")
+}
+
+/// Parses a database after checking its detached signature against `key`.
+fn verify(data: &[u8], signature: &[u8], key: &VerifyingKey) -> Result<RegionDb> {
+    let signature = Signature::from_slice(signature).context("malformed signature")?;
+
+    key.verify_strict(data, &signature)
+        .context("signature does not match the region database")?;
+
+    parse(data)
+}
+
+fn parse(data: &[u8]) -> Result<RegionDb> {
+    let db: RegionDb = serde_json::from_slice(data).context("malformed region database")?;
+    ensure!(!db.regions.is_empty(), "region database is empty");
+
+    Ok(db)
+}
+
+/// Reads the cached database, verifying it again in case it was tampered with.
+pub fn load_cached() -> Result<Option<RegionDb>> {
+    let Some(path) = cache_path() else {
+        return Ok(None);
+    };
+
+    load_from(&path, &public_key())
+}
+
+fn load_from(path: &Path, key: &VerifyingKey) -> Result<Option<RegionDb>> {
+    let data = match fs::read(path) {
+        Ok(data) => data,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let signature = fs::read(signature_path(path))?;
+
+    verify(&data, &signature, key).map(Some)
+}
+
+/// Downloads the database and its signature from `url`, caching them when valid.
+///
+/// Returns the new database if it is newer than the cached one.
+pub fn update(url: &str) -> Result<Option<RegionDb>> {
+    let path = cache_path().ok_or_else(|| anyhow!("no cache directory"))?;
+
+    update_cache(&path, url, &public_key())
+}
+
+fn update_cache(path: &Path, url: &str, key: &VerifyingKey) -> Result<Option<RegionDb>> {
+    let data = download(url)?;
+    let signature = download(&format!("{url}.{SIGNATURE_EXT}"))?;
+    let db = verify(&data, &signature, key)?;
+
+    let cached_serial = load_from(path, key)
+        .ok()
+        .flatten()
+        .map_or(0, |db| db.serial);
+    if db.serial <= cached_serial {
+        return Ok(None);
+    }
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    // a crash between the two leaves a mismatching pair, which is ignored until the next update
+    write_atomic(path, &data)?;
+    write_atomic(&signature_path(path), &signature)?;
+
+    Ok(Some(db))
+}
+
+/// Replaces the file through a temporary one, so that it is never read half written.
+fn write_atomic(path: &Path, data: &[u8]) -> Result<()> {
+    let mut temp = path.as_os_str().to_owned();
+    temp.push(".tmp");
+
+    fs::write(&temp, data)?;
+    fs::rename(&temp, path)?;
+
+    Ok(())
+}
+
+fn download(url: &str) -> Result<Vec<u8>> {
+    ureq::get(url)
+        .config()
+        .timeout_global(Some(DOWNLOAD_TIMEOUT))
+        .build()
+        .call()
+        .and_then(|mut response| response.body_mut().read_to_vec())
+        .with_context(|| format!("failed to download {url}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::{env, process, thread};
+
+    use super::*;
+
+    /// Serves `files` keyed by path over HTTP until the test process exits, returns the base URL.
+    fn serve(files: HashMap<String, Vec<u8>>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut lines = BufReader::new(&stream).lines();
+                let request = lines.next().unwrap().unwrap();
+                // skip the headers
+                for line in lines.by_ref() {
+                    if line.unwrap().is_empty() {
+                        break;
+                    }
+                }
+
+                let path = request.split(' ').nth(1).unwrap_or_default();
+                let (status, body) = match files.get(path) {
+                    Some(body) => ("200 OK", body.as_slice()),
+                    None => ("404 Not Found", &[][..]),
+                };
+                let header = format!(
+                    "HTTP/1.1 {status}\r\ncontent-length: {}\r\nconnection: close\r\n\r\n",
+                    body.len()
+                );
+                stream.write_all(header.as_bytes()).unwrap();
+                stream.write_all(body).unwrap();
+            }
+        });
+
+        base
+    }
+
+    fn database(serial: u64) -> Vec<u8> {
+        let region = Region {
+            key: "test/a".into(),
+            name: "A".into(),
+            code: "A".into(),
+            ping: "192.0.2.1".parse().unwrap(),
+            ping6: None,
+            continent: Default::default(),
+            prefixes: vec!["192.0.2.0/24".parse().unwrap()],
+        };
+
+        serde_json::to_vec(&RegionDb {
+            serial,
+            regions: vec![region],
+        })
+        .unwrap()
+    }
+
+    fn cache_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("regiondb-{}-{name}", process::id()));
+        fs::remove_dir_all(&dir).ok();
+        dir
+    }
+
+    #[test]
+    fn embedded_key_parses() {
+        public_key();
+    }
+
+    #[test]
+    fn update_caches_signed_database() {
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let data = database(3);
+        let signature = key.sign(&data).to_bytes().to_vec();
+        let base = serve(HashMap::from([
+            ("/regions.json".into(), data),
+            ("/regions.json.sig".into(), signature),
+        ]));
+
+        let dir = cache_dir("signed");
+        let path = dir.join("regions.json");
+        let url = format!("{base}/regions.json");
+
+        let db = update_cache(&path, &url, &key.verifying_key()).unwrap();
+        assert_eq!(db.map(|db| db.serial), Some(3));
+
+        // the same serial is not applied twice
+        assert!(
+            update_cache(&path, &url, &key.verifying_key())
+                .unwrap()
+                .is_none()
+        );
+
+        let cached = load_from(&path, &key.verifying_key()).unwrap().unwrap();
+        assert_eq!(cached.serial, 3);
+        assert_eq!(
+            fs::read_dir(&dir).unwrap().count(),
+            2,
+            "temporary files left"
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn update_rejects_other_key() {
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let other = SigningKey::from_bytes(&[8; 32]);
+        let data = database(1);
+        let signature = other.sign(&data).to_bytes().to_vec();
+        let base = serve(HashMap::from([
+            ("/regions.json".into(), data),
+            ("/regions.json.sig".into(), signature),
+        ]));
+
+        let dir = cache_dir("other-key");
+        let path = dir.join("regions.json");
+
+        assert!(
+            update_cache(&path, &format!("{base}/regions.json"), &key.verifying_key()).is_err()
+        );
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn update_fails_without_signature() {
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let base = serve(HashMap::from([("/regions.json".into(), database(1))]));
+
+        let dir = cache_dir("unsigned");
+        let path = dir.join("regions.json");
+
+        assert!(
+            update_cache(&path, &format!("{base}/regions.json"), &key.verifying_key()).is_err()
+        );
+        assert!(!path.exists());
+    }
+}
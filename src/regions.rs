@@ -1,69 +1,313 @@
 use std::cmp::Ordering;
+use std::collections::BTreeSet;
+use std::time::Duration;
 
+use indexmap::IndexMap;
+use iter_tools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use crate::blocking::BlockMode;
+use crate::i18n::{self, tr};
+use crate::prefixes::Continent;
 use crate::{ping, prefixes};
 
 pub struct RegionEntry {
     pub region: prefixes::Region,
     pub ping: ping::PingStatus,
+    /// Ping of the region's IPv6 target, if it has one.
+    pub ping6: Option<ping::PingStatus>,
+    pub stats: ping::PingStats,
     pub selected: bool,
 }
 
-#[derive(Clone, Copy, PartialEq, Eq)]
+impl RegionEntry {
+    /// Lowest latency among the region's targets, if any responded.
+    pub fn best_ping(&self) -> Option<Duration> {
+        std::iter::once(&self.ping)
+            .chain(self.ping6.as_ref())
+            .filter_map(|status| match status {
+                ping::PingStatus::Reachable(duration) => Some(*duration),
+                _ => None,
+            })
+            .min()
+    }
+}
+
+/// Selection the running blocking was enabled with.
+pub struct AppliedSelection {
+    /// Keys of the regions selected when blocking was enabled.
+    pub regions: BTreeSet<String>,
+    pub block_mode: BlockMode,
+}
+
+impl AppliedSelection {
+    pub fn new(regions: &IndexMap<String, RegionEntry>, block_mode: BlockMode) -> Self {
+        Self {
+            regions: regions
+                .iter()
+                .filter(|(_, entry)| entry.selected)
+                .map(|(key, _)| key.clone())
+                .collect(),
+            block_mode,
+        }
+    }
+
+    /// Whether the selection of a region changed since blocking was enabled.
+    pub fn is_pending(&self, key: &str, entry: &RegionEntry) -> bool {
+        self.regions.contains(key) != entry.selected
+    }
+
+    /// Amount of regions whose selection changed since blocking was enabled.
+    pub fn pending_count(&self, regions: &IndexMap<String, RegionEntry>) -> usize {
+        regions
+            .iter()
+            .filter(|(key, entry)| self.is_pending(key, entry))
+            .count()
+    }
+}
+
+/// Which regions are shown in the list.
+#[derive(Default)]
+pub struct RegionFilter {
+    /// Text to find in the name, code or key of the regions, ignoring case.
+    pub text: String,
+    pub selected_only: bool,
+    pub reachable_only: bool,
+    /// Hide regions slower than this many milliseconds, or not reachable.
+    pub max_ping: Option<u32>,
+}
+
+impl RegionFilter {
+    /// Whether some regions may be hidden.
+    pub fn is_active(&self) -> bool {
+        !self.text.trim().is_empty()
+            || self.selected_only
+            || self.reachable_only
+            || self.max_ping.is_some()
+    }
+
+    pub fn matches(&self, entry: &RegionEntry) -> bool {
+        let text = self.text.trim().to_lowercase();
+        if !text.is_empty()
+            && ![
+                &i18n::region_name(&entry.region),
+                &entry.region.name,
+                &entry.region.code,
+                &entry.region.key,
+            ]
+            .iter()
+            .any(|field| field.to_lowercase().contains(&text))
+        {
+            return false;
+        }
+
+        if self.selected_only && !entry.selected {
+            return false;
+        }
+
+        let best_ping = entry.best_ping();
+        if self.reachable_only && best_ping.is_none() {
+            return false;
+        }
+
+        match (self.max_ping, best_ping) {
+            (Some(max), Some(ping)) => ping.as_millis() <= u128::from(max),
+            (Some(_), None) => false,
+            (None, _) => true,
+        }
+    }
+}
+
+/// Selection change applied to every shown region at once.
+#[derive(Clone, Copy)]
+pub enum BulkSelection {
+    All,
+    None,
+    Invert,
+    /// Select the given amount of regions with the lowest average ping, unselect the others.
+    Best(usize),
+}
+
+impl BulkSelection {
+    /// Changes the selection of the regions matching `filter`.
+    pub fn apply(&self, regions: &mut IndexMap<String, RegionEntry>, filter: &RegionFilter) {
+        let shown = regions
+            .values_mut()
+            .filter(|entry| filter.matches(entry))
+            .collect_vec();
+
+        match self {
+            BulkSelection::All => shown.into_iter().for_each(|entry| entry.selected = true),
+            BulkSelection::None => shown.into_iter().for_each(|entry| entry.selected = false),
+            BulkSelection::Invert => shown
+                .into_iter()
+                .for_each(|entry| entry.selected = !entry.selected),
+            BulkSelection::Best(n) => {
+                let best = shown
+                    .into_iter()
+                    .sorted_by_key(|entry| {
+                        entry
+                            .stats
+                            .avg()
+                            .or(entry.best_ping())
+                            .unwrap_or(Duration::MAX)
+                    })
+                    .enumerate();
+
+                for (i, entry) in best {
+                    entry.selected =
+                        i < *n && (entry.stats.avg().is_some() || entry.best_ping().is_some());
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum RegionSortBy {
     Name,
+    Code,
+    Provider,
     Ping,
+    /// Packet loss of the region's IPv4 target.
+    Loss,
+    /// Selected regions first.
+    Selected,
+}
+
+impl RegionSortBy {
+    pub const ALL: [RegionSortBy; 6] = [
+        RegionSortBy::Name,
+        RegionSortBy::Code,
+        RegionSortBy::Provider,
+        RegionSortBy::Ping,
+        RegionSortBy::Loss,
+        RegionSortBy::Selected,
+    ];
+
+    /// Whether the order changes with ping measurements.
+    pub fn is_measured(&self) -> bool {
+        matches!(self, RegionSortBy::Ping | RegionSortBy::Loss)
+    }
+
+    /// Whether the values are numbers rather than text.
+    pub fn is_numeric(&self) -> bool {
+        matches!(
+            self,
+            RegionSortBy::Ping | RegionSortBy::Loss | RegionSortBy::Selected
+        )
+    }
+
+    fn cmp(&self, a: &RegionEntry, b: &RegionEntry) -> Ordering {
+        match self {
+            RegionSortBy::Name => i18n::region_name(&a.region).cmp(&i18n::region_name(&b.region)),
+            RegionSortBy::Code => a.region.code.cmp(&b.region.code),
+            RegionSortBy::Provider => a.region.provider().cmp(&b.region.provider()),
+            RegionSortBy::Ping => {
+                let a_ping = a.ping.as_millis_or(1000);
+                let b_ping = b.ping.as_millis_or(1000);
+                a_ping.cmp(&b_ping)
+            }
+            RegionSortBy::Loss => a.stats.loss().total_cmp(&b.stats.loss()),
+            RegionSortBy::Selected => b.selected.cmp(&a.selected),
+        }
+    }
 }
 
 impl std::fmt::Display for RegionSortBy {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let name = match self {
-            RegionSortBy::Name => "name",
-            RegionSortBy::Ping => "ping",
+            RegionSortBy::Name => tr!("sort-name"),
+            RegionSortBy::Code => tr!("sort-code"),
+            RegionSortBy::Provider => tr!("sort-provider"),
+            RegionSortBy::Ping => tr!("sort-ping"),
+            RegionSortBy::Loss => tr!("sort-loss"),
+            RegionSortBy::Selected => tr!("sort-selection"),
         };
         write!(f, "{name}")
     }
 }
 
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RegionGrouping {
+    None,
+    Continent,
+    Provider,
+}
+
+impl RegionGrouping {
+    pub const ALL: [RegionGrouping; 3] = [
+        RegionGrouping::None,
+        RegionGrouping::Continent,
+        RegionGrouping::Provider,
+    ];
+
+    /// Position and name of the group of a region, `None` when regions are not grouped.
+    pub fn group_of(&self, region: &prefixes::Region) -> Option<(usize, String)> {
+        match self {
+            RegionGrouping::None => None,
+            RegionGrouping::Continent => Some((
+                Continent::ALL
+                    .iter()
+                    .position(|&c| c == region.continent)
+                    .unwrap(),
+                region.continent.to_string(),
+            )),
+            RegionGrouping::Provider => Some((0, region.provider())),
+        }
+    }
+}
+
+impl std::fmt::Display for RegionGrouping {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            RegionGrouping::None => tr!("grouping-none"),
+            RegionGrouping::Continent => tr!("grouping-continent"),
+            RegionGrouping::Provider => tr!("grouping-provider"),
+        };
+        write!(f, "{name}")
+    }
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct RegionSorting {
     pub by: RegionSortBy,
     pub asc: bool,
+    /// Sorts regions equal by [`RegionSorting::by`], always ascending.
+    pub then_by: Option<RegionSortBy>,
 }
 
 impl RegionSorting {
-    pub fn ordering_name(&self) -> &'static str {
-        if self.asc { "ascending" } else { "descending" }
+    pub fn ordering_name(&self) -> String {
+        if self.asc {
+            tr!("sort-ascending")
+        } else {
+            tr!("sort-descending")
+        }
     }
 
     pub fn toggle_asc(&mut self) {
         self.asc = !self.asc
     }
 
-    pub fn next_property(&self) -> RegionSortBy {
-        match self.by {
-            RegionSortBy::Name => RegionSortBy::Ping,
-            RegionSortBy::Ping => RegionSortBy::Name,
-        }
-    }
-
-    pub fn cycle_property(&mut self) {
-        self.by = self.next_property()
+    /// Whether the order changes with ping measurements.
+    pub fn is_measured(&self) -> bool {
+        self.by.is_measured() || self.then_by.is_some_and(|by| by.is_measured())
     }
 
     pub fn as_cmp(&self) -> impl Fn(&RegionEntry, &RegionEntry) -> Ordering {
         let by = self.by;
         let asc = self.asc;
+        let then_by = self.then_by;
 
         move |a, b| {
-            let ord = match by {
-                RegionSortBy::Name => a.region.name.cmp(&b.region.name),
-                RegionSortBy::Ping => {
-                    let a_ping = a.ping.as_millis_or(1000);
-                    let b_ping = b.ping.as_millis_or(1000);
-                    a_ping.cmp(&b_ping)
-                }
-            };
+            let ord = by.cmp(a, b);
+            let ord = if asc { ord } else { ord.reverse() };
 
-            if asc { ord } else { ord.reverse() }
+            ord.then_with(|| then_by.map_or(Ordering::Equal, |then_by| then_by.cmp(a, b)))
+                .then_with(|| a.region.name.cmp(&b.region.name))
         }
     }
 }
@@ -73,6 +317,7 @@ impl Default for RegionSorting {
         Self {
             by: RegionSortBy::Name,
             asc: true,
+            then_by: None,
         }
     }
 }
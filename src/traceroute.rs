@@ -0,0 +1,415 @@
+use std::collections::BTreeMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use socket2::Type;
+#[cfg(not(target_os = "linux"))]
+use surge_ping::{AsyncSocket, Config, ICMP, Icmpv4Packet, Icmpv6Packet};
+use tokio::runtime::Runtime;
+use tokio::task::{self, JoinHandle, JoinSet};
+use tokio::time;
+
+/// Highest TTL probed before giving up on reaching the target.
+pub const MAX_HOPS: u8 = 30;
+
+const TIMEOUT: Duration = Duration::from_secs(3);
+
+const PAYLOAD: &[u8] = b"github.com/trash-pandy/ow2-server-picker";
+
+/// Destination port of the first UDP probe, the classic traceroute one.
+#[cfg(target_os = "linux")]
+const UDP_BASE_PORT: u16 = 33434;
+
+type UpdateCallback = Arc<dyn Fn() + Send + Sync>;
+
+#[derive(Clone, Debug)]
+pub struct Hop {
+    /// The endpoint which answered the probe, if any.
+    pub addr: Option<IpAddr>,
+    /// Round trip time of the probe.
+    pub rtt: Option<Duration>,
+    /// Reverse DNS name of the endpoint, once resolved.
+    pub hostname: Option<String>,
+}
+
+#[derive(Default)]
+pub struct TraceState {
+    /// Probed hops keyed by their TTL.
+    pub hops: BTreeMap<u8, Hop>,
+    /// TTL at which the target itself answered.
+    pub reached: Option<u8>,
+    /// Whether all probes have finished.
+    pub done: bool,
+    pub error: Option<String>,
+}
+
+impl TraceState {
+    /// Hops up to the target, or every probed hop if it was not reached.
+    pub fn route(&self) -> impl Iterator<Item = (&u8, &Hop)> {
+        let last = self.reached.unwrap_or(MAX_HOPS);
+        self.hops.range(..=last)
+    }
+}
+
+/// A traceroute running in the background until dropped.
+pub struct Traceroute {
+    pub key: String,
+    pub target: IpAddr,
+    state: Arc<Mutex<TraceState>>,
+    handle: JoinHandle<()>,
+}
+
+impl Traceroute {
+    /// Starts probing `target`, calling `on_update` whenever the state changes.
+    pub fn start(
+        runtime: &Runtime,
+        key: String,
+        target: IpAddr,
+        on_update: impl Fn() + Send + Sync + 'static,
+    ) -> Self {
+        let state = Arc::new(Mutex::new(TraceState::default()));
+        let on_update: UpdateCallback = Arc::new(on_update);
+
+        let handle = runtime.spawn({
+            let state = state.clone();
+
+            async move {
+                let mut probes = JoinSet::new();
+                let mut lookups = JoinSet::new();
+
+                for ttl in 1..=MAX_HOPS {
+                    probes.spawn(async move { (ttl, probe(target, ttl).await) });
+                }
+
+                while let Some(Ok((ttl, result))) = probes.join_next().await {
+                    match result {
+                        Ok((hop, reached)) => {
+                            let addr = hop.addr;
+                            {
+                                let mut state = state.lock().unwrap();
+                                if reached && state.reached.is_none_or(|r| ttl < r) {
+                                    state.reached = Some(ttl);
+                                }
+                                state.hops.insert(ttl, hop);
+                            }
+
+                            if let Some(addr) = addr {
+                                lookups.spawn(reverse_lookup(
+                                    state.clone(),
+                                    on_update.clone(),
+                                    ttl,
+                                    addr,
+                                ));
+                            }
+                        }
+                        Err(e) => {
+                            state.lock().unwrap().error = Some(e.to_string());
+                            probes.abort_all();
+                        }
+                    }
+
+                    on_update();
+                }
+
+                state.lock().unwrap().done = true;
+                on_update();
+
+                lookups.join_all().await;
+            }
+        });
+
+        Self {
+            key,
+            target,
+            state,
+            handle,
+        }
+    }
+
+    pub fn state(&self) -> MutexGuard<'_, TraceState> {
+        self.state.lock().unwrap()
+    }
+}
+
+impl Drop for Traceroute {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+async fn reverse_lookup(
+    state: Arc<Mutex<TraceState>>,
+    on_update: UpdateCallback,
+    ttl: u8,
+    addr: IpAddr,
+) {
+    let Ok(Ok(hostname)) = task::spawn_blocking(move || dns_lookup::lookup_addr(&addr)).await
+    else {
+        return;
+    };
+
+    // unresolved addresses come back in their numeric form
+    if hostname == addr.to_string() {
+        return;
+    }
+
+    if let Some(hop) = state.lock().unwrap().hops.get_mut(&ttl) {
+        hop.hostname = Some(hostname);
+    }
+    on_update();
+}
+
+/// Sends a single UDP datagram with the given TTL and waits for the ICMP error it triggers.
+///
+/// Unlike ICMP datagram sockets, UDP sockets receive the errors of intermediate routers without
+/// privileges through their error queue. Returns the hop along with whether it was the target
+/// itself.
+#[cfg(target_os = "linux")]
+async fn probe(target: IpAddr, ttl: u8) -> Result<(Hop, bool)> {
+    use nix::sys::socket::{self, sockopt};
+    use socket2::{Domain, Protocol, Socket};
+    use tokio::io::Interest;
+    use tokio::io::unix::AsyncFd;
+
+    let dest = SocketAddr::new(target, UDP_BASE_PORT + u16::from(ttl));
+    let socket = Socket::new(Domain::for_address(dest), Type::DGRAM, Some(Protocol::UDP))?;
+    socket.set_nonblocking(true)?;
+    match target {
+        IpAddr::V4(_) => {
+            socket.set_ttl(ttl.into())?;
+            socket::setsockopt(&socket, sockopt::Ipv4RecvErr, &true)?;
+        }
+        IpAddr::V6(_) => {
+            socket.set_unicast_hops_v6(ttl.into())?;
+            socket::setsockopt(&socket, sockopt::Ipv6RecvErr, &true)?;
+        }
+    }
+    socket.connect(&dest.into())?;
+    socket.send(PAYLOAD)?;
+    let sent = Instant::now();
+
+    let socket = AsyncFd::with_interest(socket, Interest::ERROR)?;
+    let reply = time::timeout(TIMEOUT, async {
+        loop {
+            let mut guard = socket.ready(Interest::ERROR).await?;
+            if let Ok(result) = guard.try_io(|socket| read_error(socket.get_ref(), target))
+                && let Some(reply) = result?
+            {
+                return Ok::<_, std::io::Error>(reply);
+            }
+        }
+    })
+    .await;
+
+    Ok(match reply {
+        Ok(reply) => {
+            let (addr, reached) = reply?;
+            let hop = Hop {
+                addr: Some(addr),
+                rtt: Some(sent.elapsed()),
+                hostname: None,
+            };
+            (hop, reached)
+        }
+        Err(_) => {
+            let hop = Hop {
+                addr: None,
+                rtt: None,
+                hostname: None,
+            };
+            (hop, false)
+        }
+    })
+}
+
+/// Reads the next ICMP error of the socket's error queue.
+///
+/// Returns the endpoint which sent it along with whether it was the target itself, or `None`
+/// for errors which do not come from the network.
+#[cfg(target_os = "linux")]
+fn read_error(socket: &socket2::Socket, target: IpAddr) -> std::io::Result<Option<(IpAddr, bool)>> {
+    use std::io::IoSliceMut;
+    use std::net::{Ipv4Addr, Ipv6Addr};
+    use std::os::fd::AsRawFd;
+
+    use nix::sys::socket::{self, ControlMessageOwned, MsgFlags, SockaddrStorage};
+
+    let mut buf = [0u8; 512];
+    let mut iov = [IoSliceMut::new(&mut buf)];
+    let mut cmsg = nix::cmsg_space!(libc::sock_extended_err, libc::sockaddr_in6);
+    let msg = socket::recvmsg::<SockaddrStorage>(
+        socket.as_raw_fd(),
+        &mut iov,
+        Some(&mut cmsg),
+        MsgFlags::MSG_ERRQUEUE,
+    )?;
+
+    for cmsg in msg.cmsgs()? {
+        let (err, time_exceeded, offender) = match cmsg {
+            ControlMessageOwned::Ipv4RecvErr(err, offender) => (
+                err,
+                err.ee_type == 11,
+                offender
+                    .map(|addr| IpAddr::from(Ipv4Addr::from(u32::from_be(addr.sin_addr.s_addr)))),
+            ),
+            ControlMessageOwned::Ipv6RecvErr(err, offender) => (
+                err,
+                err.ee_type == 3,
+                offender.map(|addr| IpAddr::from(Ipv6Addr::from(addr.sin6_addr.s6_addr))),
+            ),
+            _ => continue,
+        };
+
+        if err.ee_origin != libc::SO_EE_ORIGIN_ICMP && err.ee_origin != libc::SO_EE_ORIGIN_ICMP6 {
+            continue;
+        }
+
+        if let Some(offender) = offender {
+            // the target rejects the datagram itself, usually as port unreachable
+            return Ok(Some((offender, !time_exceeded && offender == target)));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Sends a single echo request with the given TTL and waits for whoever answers it.
+///
+/// Returns the hop along with whether it was the target itself.
+#[cfg(not(target_os = "linux"))]
+async fn probe(target: IpAddr, ttl: u8) -> Result<(Hop, bool)> {
+    // the pinger identifies its requests with the process id, keep its replies apart
+    let ident = std::process::id() as u16 ^ 0x7472;
+
+    let config = Config::builder()
+        .kind(match target {
+            IpAddr::V4(_) => ICMP::V4,
+            IpAddr::V6(_) => ICMP::V6,
+        })
+        .sock_type_hint(Type::RAW)
+        .ttl(ttl.into())
+        .build();
+    let socket = AsyncSocket::new(&config)?;
+
+    let mut packet = echo_request(target, ident, ttl.into());
+    socket
+        .send_to(&mut packet, &SocketAddr::new(target, 0))
+        .await?;
+    let sent = Instant::now();
+
+    let reply = time::timeout(TIMEOUT, async {
+        let mut buf = [0u8; 2048];
+
+        loop {
+            let (size, from) = socket.recv_from(&mut buf).await?;
+            if let Some(reached) = match_reply(&socket, &buf[..size], from.ip(), target, ident, ttl)
+            {
+                return Ok::<_, std::io::Error>((from.ip(), reached));
+            }
+        }
+    })
+    .await;
+
+    Ok(match reply {
+        Ok(reply) => {
+            let (addr, reached) = reply?;
+            let hop = Hop {
+                addr: Some(addr),
+                rtt: Some(sent.elapsed()),
+                hostname: None,
+            };
+            (hop, reached)
+        }
+        Err(_) => {
+            let hop = Hop {
+                addr: None,
+                rtt: None,
+                hostname: None,
+            };
+            (hop, false)
+        }
+    })
+}
+
+/// Checks whether a received packet answers our probe.
+///
+/// Returns whether the answer is an echo reply from the target itself.
+#[cfg(not(target_os = "linux"))]
+fn match_reply(
+    socket: &AsyncSocket,
+    buf: &[u8],
+    from: IpAddr,
+    target: IpAddr,
+    ident: u16,
+    ttl: u8,
+) -> Option<bool> {
+    let (reply_ident, seq, echo_reply) = match from {
+        IpAddr::V4(from) => {
+            let IpAddr::V4(local) = socket.local_addr().ok()?.ip() else {
+                return None;
+            };
+            let packet = Icmpv4Packet::decode(buf, socket.get_type(), from, local).ok()?;
+            (
+                packet.get_identifier(),
+                packet.get_sequence(),
+                packet.get_icmp_type().0 == 0,
+            )
+        }
+        IpAddr::V6(from) => {
+            let packet = Icmpv6Packet::decode(buf, from).ok()?;
+            (
+                packet.get_identifier(),
+                packet.get_sequence(),
+                packet.get_icmpv6_type().0 == 129,
+            )
+        }
+    };
+
+    if seq.into_u16() != u16::from(ttl) || reply_ident.into_u16() != ident {
+        return None;
+    }
+
+    // echo replies only come from the target, anything else answers someone else's request
+    if echo_reply && from != target {
+        return None;
+    }
+
+    Some(echo_reply)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn echo_request(target: IpAddr, ident: u16, seq: u16) -> Vec<u8> {
+    let echo_type = match target {
+        IpAddr::V4(_) => 8,
+        IpAddr::V6(_) => 128,
+    };
+
+    let mut packet = vec![echo_type, 0, 0, 0];
+    packet.extend_from_slice(&ident.to_be_bytes());
+    packet.extend_from_slice(&seq.to_be_bytes());
+    packet.extend_from_slice(PAYLOAD);
+
+    // the kernel fills in the checksum of ICMPv6 packets
+    if target.is_ipv4() {
+        let checksum = checksum(&packet);
+        packet[2..4].copy_from_slice(&checksum.to_be_bytes());
+    }
+
+    packet
+}
+
+#[cfg(not(target_os = "linux"))]
+fn checksum(data: &[u8]) -> u16 {
+    let mut sum = data
+        .chunks(2)
+        .map(|chunk| u32::from(u16::from_be_bytes([chunk[0], *chunk.get(1).unwrap_or(&0)])))
+        .sum::<u32>();
+
+    while sum > 0xffff {
+        sum = (sum & 0xffff) + (sum >> 16);
+    }
+
+    !(sum as u16)
+}
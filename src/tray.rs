@@ -0,0 +1,214 @@
+//! Status notifier icon controlling blocking while the window is hidden.
+
+use anyhow::Result;
+use eframe::egui;
+use ksni::menu::{RadioGroup, RadioItem, StandardItem, SubMenu};
+use ksni::{MenuItem, ToolTip, TrayMethods};
+use tokio::runtime::Runtime;
+use tokio::sync::mpsc;
+
+use crate::i18n::tr;
+
+/// Action requested from the icon's menu.
+pub enum TrayCommand {
+    /// Show the window, or hide it when the menu offers to.
+    ToggleWindow,
+    Enable,
+    Disable,
+    /// Apply the profile of the given name.
+    Profile(String),
+    Quit,
+}
+
+/// What the icon shows.
+#[derive(Clone, Default, PartialEq)]
+pub struct TrayState {
+    /// Whether blocking is enabled.
+    pub active: bool,
+
+    /// Names of the saved profiles.
+    pub profiles: Vec<String>,
+
+    pub active_profile: Option<String>,
+
+    /// Whether the menu offers to show the window rather than to hide it.
+    pub show_window: bool,
+}
+
+struct StatusIcon {
+    state: TrayState,
+    commands: mpsc::UnboundedSender<TrayCommand>,
+    ctx: egui::Context,
+}
+
+impl StatusIcon {
+    fn send(&self, command: TrayCommand) {
+        if self.commands.send(command).is_ok() {
+            self.ctx.request_repaint();
+        }
+    }
+}
+
+impl ksni::Tray for StatusIcon {
+    fn id(&self) -> String {
+        env!("CARGO_PKG_NAME").into()
+    }
+
+    fn title(&self) -> String {
+        tr!("app-title")
+    }
+
+    fn icon_name(&self) -> String {
+        if self.state.active {
+            "security-high"
+        } else {
+            "security-low"
+        }
+        .into()
+    }
+
+    fn tool_tip(&self) -> ToolTip {
+        let description = match (&self.state.active, &self.state.active_profile) {
+            (true, Some(profile)) => tr!("tray-enabled-profile", profile = profile.as_str()),
+            (true, None) => tr!("tray-enabled"),
+            (false, _) => tr!("tray-disabled"),
+        };
+
+        ToolTip {
+            title: tr!("app-title"),
+            description,
+            ..Default::default()
+        }
+    }
+
+    fn activate(&mut self, _x: i32, _y: i32) {
+        self.send(TrayCommand::ToggleWindow);
+    }
+
+    fn menu(&self) -> Vec<MenuItem<Self>> {
+        let toggle_label = if self.state.show_window {
+            tr!("tray-show")
+        } else {
+            tr!("tray-hide")
+        };
+
+        let mut menu = vec![
+            StandardItem {
+                label: toggle_label,
+                activate: Box::new(|this: &mut Self| this.send(TrayCommand::ToggleWindow)),
+                ..Default::default()
+            }
+            .into(),
+            MenuItem::Separator,
+            StandardItem {
+                label: tr!("enable"),
+                activate: Box::new(|this: &mut Self| this.send(TrayCommand::Enable)),
+                ..Default::default()
+            }
+            .into(),
+            StandardItem {
+                label: tr!("disable"),
+                enabled: self.state.active,
+                activate: Box::new(|this: &mut Self| this.send(TrayCommand::Disable)),
+                ..Default::default()
+            }
+            .into(),
+        ];
+
+        if !self.state.profiles.is_empty() {
+            let selected = self
+                .state
+                .active_profile
+                .as_ref()
+                .and_then(|active| self.state.profiles.iter().position(|name| name == active))
+                .unwrap_or(usize::MAX);
+
+            menu.push(
+                SubMenu {
+                    label: tr!("profiles"),
+                    submenu: vec![
+                        RadioGroup {
+                            selected,
+                            select: Box::new(|this: &mut Self, i| {
+                                if let Some(name) = this.state.profiles.get(i).cloned() {
+                                    this.send(TrayCommand::Profile(name));
+                                }
+                            }),
+                            options: self
+                                .state
+                                .profiles
+                                .iter()
+                                .map(|name| RadioItem {
+                                    label: name.clone(),
+                                    ..Default::default()
+                                })
+                                .collect(),
+                        }
+                        .into(),
+                    ],
+                    ..Default::default()
+                }
+                .into(),
+            );
+        }
+
+        menu.extend([
+            MenuItem::Separator,
+            StandardItem {
+                label: tr!("tray-quit"),
+                icon_name: "application-exit".into(),
+                activate: Box::new(|this: &mut Self| this.send(TrayCommand::Quit)),
+                ..Default::default()
+            }
+            .into(),
+        ]);
+
+        menu
+    }
+}
+
+/// The running status notifier icon.
+pub struct TrayIcon {
+    handle: ksni::Handle<StatusIcon>,
+    commands: mpsc::UnboundedReceiver<TrayCommand>,
+    state: TrayState,
+}
+
+impl TrayIcon {
+    /// Registers the icon, fails when no status notifier host is running.
+    pub fn spawn(runtime: &Runtime, ctx: egui::Context) -> Result<Self> {
+        let (commands_tx, commands) = mpsc::unbounded_channel();
+        let icon = StatusIcon {
+            state: TrayState::default(),
+            commands: commands_tx,
+            ctx,
+        };
+
+        let handle = runtime.block_on(icon.spawn())?;
+
+        Ok(Self {
+            handle,
+            commands,
+            state: TrayState::default(),
+        })
+    }
+
+    /// Next action requested from the menu, if any.
+    pub fn try_recv(&mut self) -> Option<TrayCommand> {
+        self.commands.try_recv().ok()
+    }
+
+    /// Changes what the icon shows, the update is sent only when something changed.
+    pub fn set_state(&mut self, runtime: &Runtime, state: TrayState) {
+        if state == self.state {
+            return;
+        }
+
+        self.state = state.clone();
+
+        let handle = self.handle.clone();
+        runtime.spawn(async move {
+            handle.update(|icon| icon.state = state).await;
+        });
+    }
+}
@@ -0,0 +1,241 @@
+use std::fmt::Display;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result, bail};
+use ipnetwork::IpNetwork;
+
+use crate::cidr;
+use crate::i18n::tr;
+use crate::prefixes::{self, Region};
+use crate::regiondb::RegionDb;
+
+#[derive(clap::Args)]
+pub struct ValidateArgs {
+    /// region database to check instead of the active region list
+    file: Option<PathBuf>,
+}
+
+/// A problem found in a region list.
+pub enum Issue {
+    /// A prefix that could not be parsed.
+    Invalid {
+        key: String,
+        value: String,
+        error: String,
+    },
+    /// A prefix whose address is not the first of its network (ex: `10.0.0.1/8`).
+    HostBits { key: String, prefix: IpNetwork },
+    /// A prefix listed several times by the same region.
+    Duplicate { key: String, prefix: IpNetwork },
+    /// A prefix contained in another prefix of the same region.
+    Subsumed {
+        key: String,
+        prefix: IpNetwork,
+        by: IpNetwork,
+    },
+    /// Prefixes of two regions sharing addresses, blocking one blocks part of the other.
+    Overlap {
+        key: String,
+        prefix: IpNetwork,
+        other_key: String,
+        other_prefix: IpNetwork,
+    },
+}
+
+impl Display for Issue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            Issue::Invalid { key, value, error } => tr!(
+                "issue-invalid",
+                key = key.as_str(),
+                value = value.as_str(),
+                error = error.as_str(),
+            ),
+            Issue::HostBits { key, prefix } => tr!(
+                "issue-host-bits",
+                key = key.as_str(),
+                prefix = prefix.to_string(),
+                network = format!("{}/{}", prefix.network(), prefix.prefix()),
+            ),
+            Issue::Duplicate { key, prefix } => tr!(
+                "issue-duplicate",
+                key = key.as_str(),
+                prefix = prefix.to_string(),
+            ),
+            Issue::Subsumed { key, prefix, by } => tr!(
+                "issue-subsumed",
+                key = key.as_str(),
+                prefix = prefix.to_string(),
+                by = by.to_string(),
+            ),
+            Issue::Overlap {
+                key,
+                prefix,
+                other_key,
+                other_prefix,
+            } => tr!(
+                "issue-overlap",
+                key = key.as_str(),
+                prefix = prefix.to_string(),
+                other_key = other_key.as_str(),
+                other_prefix = other_prefix.to_string(),
+            ),
+        };
+        f.write_str(&text)
+    }
+}
+
+pub fn validate_main(args: ValidateArgs) -> Result<()> {
+    let issues = match args.file {
+        Some(file) => {
+            let data = std::fs::read(&file)?;
+            let db: RegionDb = serde_json::from_slice(&data)
+                .with_context(|| format!("malformed region database {}", file.display()))?;
+            validate(&db.regions).iter().map(Issue::to_string).collect()
+        }
+        None => prefixes::load_with_warnings().1,
+    };
+
+    for issue in &issues {
+        println!("{issue}");
+    }
+
+    if !issues.is_empty() {
+        bail!("found {} issues", issues.len());
+    }
+
+    println!("no issues found");
+    Ok(())
+}
+
+/// Checks the prefixes of every region, and every pair of regions for overlaps.
+pub fn validate(regions: &[Region]) -> Vec<Issue> {
+    let mut issues = Vec::new();
+
+    for region in regions {
+        let key = &region.key;
+
+        for (i, prefix) in region.prefixes.iter().enumerate() {
+            if prefix.ip() != prefix.network() {
+                issues.push(Issue::HostBits {
+                    key: key.clone(),
+                    prefix: *prefix,
+                });
+            }
+
+            let earlier = &region.prefixes[..i];
+            if earlier.iter().any(|p| same_network(p, prefix)) {
+                issues.push(Issue::Duplicate {
+                    key: key.clone(),
+                    prefix: *prefix,
+                });
+            } else if let Some(by) = region
+                .prefixes
+                .iter()
+                .find(|p| !same_network(p, prefix) && cidr::contains(p, prefix))
+            {
+                issues.push(Issue::Subsumed {
+                    key: key.clone(),
+                    prefix: *prefix,
+                    by: *by,
+                });
+            }
+        }
+    }
+
+    for (i, region) in regions.iter().enumerate() {
+        for other in &regions[i + 1..] {
+            for prefix in &region.prefixes {
+                for other_prefix in other.prefixes.iter().filter(|p| cidr::overlaps(p, prefix)) {
+                    issues.push(Issue::Overlap {
+                        key: region.key.clone(),
+                        prefix: *prefix,
+                        other_key: other.key.clone(),
+                        other_prefix: *other_prefix,
+                    });
+                }
+            }
+        }
+    }
+
+    issues
+}
+
+fn same_network(a: &IpNetwork, b: &IpNetwork) -> bool {
+    a.network() == b.network() && a.prefix() == b.prefix()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn region(key: &str, prefixes: &[&str]) -> Region {
+        Region {
+            key: key.into(),
+            name: key.into(),
+            code: key.into(),
+            ping: "192.0.2.1".parse().unwrap(),
+            ping6: None,
+            continent: Default::default(),
+            prefixes: prefixes.iter().map(|p| p.parse().unwrap()).collect(),
+        }
+    }
+
+    #[test]
+    fn clean_database() {
+        let regions = [
+            region("a", &["10.0.0.0/24", "10.0.2.0/23", "2001:db8::/32"]),
+            region("b", &["10.0.1.0/24", "2001:db9::/32"]),
+        ];
+
+        assert!(validate(&regions).is_empty());
+    }
+
+    #[test]
+    fn host_bits() {
+        let issues = validate(&[region("a", &["10.0.0.1/24"])]);
+
+        assert!(matches!(
+            issues.as_slice(),
+            [Issue::HostBits { key, prefix }] if key == "a" && prefix.to_string() == "10.0.0.1/24"
+        ));
+    }
+
+    #[test]
+    fn duplicate() {
+        let issues = validate(&[region("a", &["10.0.0.0/24", "10.0.0.0/24"])]);
+
+        assert!(matches!(
+            issues.as_slice(),
+            [Issue::Duplicate { key, prefix }] if key == "a" && prefix.to_string() == "10.0.0.0/24"
+        ));
+    }
+
+    #[test]
+    fn subsumed() {
+        let issues = validate(&[region("a", &["10.0.0.0/16", "10.0.3.0/24"])]);
+
+        assert!(matches!(
+            issues.as_slice(),
+            [Issue::Subsumed { key, prefix, by }]
+                if key == "a" && prefix.to_string() == "10.0.3.0/24" && by.to_string() == "10.0.0.0/16"
+        ));
+    }
+
+    #[test]
+    fn cross_region_overlap() {
+        let issues = validate(&[
+            region("a", &["10.0.0.0/23"]),
+            region("b", &["10.0.1.0/24", "10.1.0.0/24"]),
+        ]);
+
+        assert!(matches!(
+            issues.as_slice(),
+            [Issue::Overlap { key, prefix, other_key, other_prefix }]
+                if key == "a"
+                    && prefix.to_string() == "10.0.0.0/23"
+                    && other_key == "b"
+                    && other_prefix.to_string() == "10.0.1.0/24"
+        ));
+    }
+}
@@ -1,8 +1,9 @@
-use eframe::egui::{Response, RichText, Ui};
+use eframe::egui::{Response, RichText, Ui, WidgetInfo, WidgetType};
 use egui_taffy::taffy::prelude::{auto, length, percent};
 use egui_taffy::taffy::{self, Style};
 use egui_taffy::{TuiBuilderLogic, tui};
 
+use crate::i18n::tr;
 use crate::ping;
 
 pub fn prefix_widget(
@@ -11,9 +12,11 @@ pub fn prefix_widget(
     code: &str,
     selected: bool,
     ping: &ping::PingStatus,
+    ping6: Option<&ping::PingStatus>,
+    pending: bool,
 ) -> Response {
     let button_width = ui.available_width() - ui.spacing().item_spacing.x - 12.;
-    tui(ui, ui.id().with(name).with(code))
+    let response = tui(ui, ui.id().with(name).with(code))
         .reserve_available_width()
         .style(Style {
             flex_direction: taffy::FlexDirection::Column,
@@ -45,23 +48,58 @@ pub fn prefix_widget(
                         let vis = tui.egui_ui_mut().visuals_mut();
                         vis.widgets.noninteractive.fg_stroke = vis.widgets.active.fg_stroke;
                     }
-                    tui.label(RichText::new(name).size(18.));
+                    // selection is not only conveyed by color
+                    let title = if selected {
+                        format!("\u{2714} {name}")
+                    } else {
+                        name.to_string()
+                    };
+                    tui.label(RichText::new(title).size(18.));
 
-                    tui.label(
-                        RichText::new(format!(
-                            "{} · {}",
+                    let text = match ping6 {
+                        Some(ping6) => format!(
+                            "{} · v4 {} · v6 {}",
                             code,
-                            match ping {
-                                ping::PingStatus::Unknown => "...".to_string(),
-                                ping::PingStatus::Reachable(duration) =>
-                                    format!("{} ms", duration.as_millis()),
-                                ping::PingStatus::Unreachable => "Unreachable".to_string(),
-                            },
-                        ))
-                        .size(11.),
-                    );
+                            ping_text(ping),
+                            ping_text(ping6)
+                        ),
+                        None => format!("{} · {}", code, ping_text(ping)),
+                    };
+                    let text = if pending {
+                        format!("{text} · {}", tr!("region-not-applied"))
+                    } else {
+                        text
+                    };
+                    tui.label(RichText::new(text).size(11.));
                 });
             })
             .response
-        })
+        });
+
+    response.widget_info(|| {
+        let mut description = tr!(
+            "region-description",
+            name = name,
+            code = code,
+            ping = ping_text(ping)
+        );
+        if let Some(ping6) = ping6 {
+            description.push_str(&tr!("region-description-v6", ping = ping_text(ping6)));
+        }
+        if pending {
+            description.push_str(&tr!("region-description-pending"));
+        }
+
+        WidgetInfo::selected(WidgetType::SelectableLabel, true, selected, description)
+    });
+
+    response
+}
+
+fn ping_text(ping: &ping::PingStatus) -> String {
+    match ping {
+        ping::PingStatus::Unknown => "...".to_string(),
+        ping::PingStatus::Reachable(duration) => format!("{} ms", duration.as_millis()),
+        ping::PingStatus::Unreachable => tr!("ping-unreachable"),
+    }
 }